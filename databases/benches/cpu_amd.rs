@@ -1,8 +1,9 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use databases::cpu::CpuCache;
+use std::hint::black_box;
 
 pub fn criterion_benchmark(c: &mut Criterion) {
-    let mut cache = CpuCache::new();
+    let cache = CpuCache::new();
 
     c.bench_function("5 CPU AMD lookup (no repeats)", |b| {
         b.iter(|| {
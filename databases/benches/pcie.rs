@@ -1,5 +1,8 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use databases::pcie::PcieCache;
+use std::hint::black_box;
+
+const REPEATED_ID: &str = "PCI\\VEN_8086&DEV_A123&SUBSYS_9C551019&REV_31\\3&11583659&0&FC";
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     let cache = PcieCache::new();
@@ -22,6 +25,15 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             }
         })
     });
+
+    // an intel device, so every lookup goes through one of the longest device lists
+    c.bench_function("1000 repeated PCIe lookups", |b| {
+        b.iter(|| {
+            for _ in 0..1000 {
+                cache.find(black_box(REPEATED_ID)).unwrap();
+            }
+        })
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -1,5 +1,8 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use databases::usb::UsbCache;
+use std::hint::black_box;
+
+const REPEATED_ID: &str = "USB\\VID_046D&PID_C336&MI_00\\7&20BE3E95&0&0000";
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     let cache = UsbCache::new();
@@ -22,6 +25,15 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             }
         })
     });
+
+    // a logitech device, one of the larger vendors in usb.ids
+    c.bench_function("1000 repeated USB lookups", |b| {
+        b.iter(|| {
+            for _ in 0..1000 {
+                cache.find(black_box(REPEATED_ID)).unwrap();
+            }
+        })
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
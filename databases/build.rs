@@ -1,17 +1,272 @@
 use parsing::bugcheck::CodeCache;
-use parsing::cpu::IntermediateCpuCache;
+use parsing::cpu::{Cpu, IntermediateCpuCache};
+use parsing::pcie::PcieCache;
+use parsing::usb::UsbCache;
 use std::collections::HashSet;
 use std::fs::File;
+use std::hash::Hash;
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
 fn main() {
-    // TODO: configure cargo to rerun only if the database changes
-    // println!("cargo::rerun-if-changed=../parsing/src/cpu/amd/input.json");
-    // println!("cargo::rerun-if-changed=build.rs");
-    gen_amd_cpus();
-    gen_intel_cpus();
+    // only regenerate when this script or one of the databases it reads changes
+    for input in [
+        "build.rs",
+        "../parsing/src/cpu/amd/input.csv",
+        "../parsing/src/cpu/intel/chunks",
+        "../parsing/src/cpu/qualcomm/input.csv",
+        "../parsing/src/bugcheck/input.md",
+        "../parsing/src/pcie/pci.ids.txt",
+        "../parsing/src/usb/usb.ids.txt",
+    ] {
+        println!("cargo::rerun-if-changed={input}");
+    }
+    // the cpu databases are parsed once and shared between the generators
+    let cpu_cache = IntermediateCpuCache::new();
+    gen_amd_cpus(&cpu_cache);
+    gen_intel_cpus(&cpu_cache);
+    gen_qualcomm_cpus(&cpu_cache);
     gen_bugcheck();
+    gen_pcie();
+    gen_usb();
+}
+
+/// Write a static map of ids to names into `generated_file`. Duplicate ids show up in a few spots in the databases,
+/// so only the first name for an id is kept
+fn write_name_map<'a, K>(
+    generated_file: &mut impl Write,
+    doc: &str,
+    name: &str,
+    key_type: &str,
+    entries: impl IntoIterator<Item = (K, &'a str)>,
+) where
+    K: phf_shared::PhfHash + phf_shared::FmtConst + Eq + Hash + Copy,
+{
+    let mut generated_map = phf_codegen::Map::new();
+    let mut keys: HashSet<K> = HashSet::new();
+    for (key, value) in entries {
+        if keys.insert(key) {
+            generated_map.entry(key, format!("{value:?}"));
+        }
+    }
+    writeln!(
+        generated_file,
+        "/// {doc}\n#[rustfmt::skip]\n#[allow(clippy::all)]\npub static {name}: phf::Map<{key_type}, &'static str> = {};",
+        generated_map.build()
+    )
+    .unwrap();
+}
+
+/// Parse the pci.ids database and generate `src/pcie/pcie_codegen.rs`. Ids are packed together into a single integer
+/// key for each level of the tree, so each lookup is a single hash
+fn gen_pcie() {
+    let destination = Path::new("src/pcie/").join("pcie_codegen.rs");
+    let mut generated_file = BufWriter::new(File::create(destination).unwrap());
+    writeln!(
+        &mut generated_file,
+        "// This file was autogenerated by build.rs"
+    )
+    .unwrap();
+    let cache = PcieCache::new();
+    let vendors = cache.vendors.values();
+    let devices = vendors
+        .clone()
+        .flat_map(|v| v.devices.values().map(move |d| (v.id, d)));
+    let classes = cache.classes.values();
+    let subclasses = classes
+        .clone()
+        .flat_map(|c| c.subclasses.values().map(move |sc| (c.id, sc)));
+    write_name_map(
+        &mut generated_file,
+        "Vendor names, keyed by vendor id",
+        "PCIE_VENDORS",
+        "u16",
+        vendors.map(|v| (v.id, v.name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Device names, keyed by `vendor << 16 | device`",
+        "PCIE_DEVICES",
+        "u32",
+        devices
+            .clone()
+            .map(|(vid, d)| ((vid as u32) << 16 | d.id as u32, d.name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Subsystem names, keyed by `vendor << 48 | device << 32 | subsystem vendor << 16 | subsystem device`",
+        "PCIE_SUBSYSTEMS",
+        "u64",
+        devices.flat_map(|(vid, d)| {
+            d.subsystems.iter().map(move |s| {
+                (
+                    (vid as u64) << 48 | (d.id as u64) << 32 | (s.vendor_id as u64) << 16 | s.id as u64,
+                    s.name.as_str(),
+                )
+            })
+        }),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Class names, keyed by class id",
+        "PCIE_CLASSES",
+        "u8",
+        classes.map(|c| (c.id, c.name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Subclass names, keyed by `class << 8 | subclass`",
+        "PCIE_SUBCLASSES",
+        "u16",
+        subclasses
+            .clone()
+            .map(|(cid, sc)| ((cid as u16) << 8 | sc.id as u16, sc.name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Programming interface names, keyed by `class << 16 | subclass << 8 | programming interface`",
+        "PCIE_PROG_IFS",
+        "u32",
+        subclasses.flat_map(|(cid, sc)| {
+            sc.prog_ifs.values().map(move |p| {
+                (
+                    (cid as u32) << 16 | (sc.id as u32) << 8 | p.id as u32,
+                    p.name.as_str(),
+                )
+            })
+        }),
+    );
+}
+
+/// Parse the usb.ids database and generate `src/usb/usb_codegen.rs`, keys are packed the same way as [gen_pcie]
+fn gen_usb() {
+    let destination = Path::new("src/usb/").join("usb_codegen.rs");
+    let mut generated_file = BufWriter::new(File::create(destination).unwrap());
+    writeln!(
+        &mut generated_file,
+        "// This file was autogenerated by build.rs"
+    )
+    .unwrap();
+    let cache = UsbCache::new();
+    let vendors = cache.vendors.values();
+    let subclasses = cache
+        .classes
+        .values()
+        .flat_map(|c| c.subclasses.iter().map(move |sc| (c.id, sc)));
+    write_name_map(
+        &mut generated_file,
+        "Vendor names, keyed by vendor id",
+        "USB_VENDORS",
+        "u16",
+        vendors.clone().map(|v| (v.id, v.name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Device names, keyed by `vendor << 16 | device`",
+        "USB_DEVICES",
+        "u32",
+        vendors.flat_map(|v| {
+            v.devices
+                .values()
+                .map(move |d| ((v.id as u32) << 16 | d.id as u32, d.name.as_str()))
+        }),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Class names, keyed by class id",
+        "USB_CLASSES",
+        "u8",
+        cache.classes.values().map(|c| (c.id, c.name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Subclass names, keyed by `class << 8 | subclass`",
+        "USB_SUBCLASSES",
+        "u16",
+        subclasses
+            .clone()
+            .map(|(cid, sc)| ((cid as u16) << 8 | sc.id as u16, sc.name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Protocol names, keyed by `class << 16 | subclass << 8 | protocol`",
+        "USB_PROTOCOLS",
+        "u32",
+        subclasses.flat_map(|(cid, sc)| {
+            sc.protocols.iter().map(move |p| {
+                (
+                    (cid as u32) << 16 | (sc.id as u32) << 8 | p.id as u32,
+                    p.name.as_str(),
+                )
+            })
+        }),
+    );
+    write_name_map(
+        &mut generated_file,
+        "HID usage page names, keyed by usage page",
+        "USB_HID_USAGE_PAGES",
+        "u16",
+        cache
+            .hid_usage_pages
+            .values()
+            .map(|p| (p.id, p.name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "HID usage names, keyed by `usage page << 16 | usage`",
+        "USB_HID_USAGES",
+        "u32",
+        cache.hid_usage_pages.values().flat_map(|p| {
+            p.usages
+                .iter()
+                .map(move |u| ((p.id as u32) << 16 | u.id as u32, u.name.as_str()))
+        }),
+    );
+    write_name_map(
+        &mut generated_file,
+        "HID report descriptor item names, keyed by the item prefix with the size bits cleared",
+        "USB_REPORT_ITEMS",
+        "u8",
+        cache
+            .report_items
+            .iter()
+            .map(|(id, name)| (*id, name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Physical descriptor bias names, keyed by bias",
+        "USB_BIASES",
+        "u8",
+        cache.biases.iter().map(|(id, name)| (*id, name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Language names, keyed by the primary language id",
+        "USB_LANGUAGES",
+        "u16",
+        cache.languages.values().map(|l| (l.id, l.name.as_str())),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Dialect names, keyed by the full language id, `dialect << 10 | primary language`",
+        "USB_DIALECTS",
+        "u16",
+        cache.languages.values().flat_map(|l| {
+            l.dialects
+                .iter()
+                .map(move |d| ((d.id as u16) << 10 | l.id, d.name.as_str()))
+        }),
+    );
+    write_name_map(
+        &mut generated_file,
+        "Audio class terminal type names, keyed by terminal type",
+        "USB_AUDIO_TERMINALS",
+        "u16",
+        cache
+            .audio_terminals
+            .iter()
+            .map(|(id, name)| (*id, name.as_str())),
+    );
 }
 
 /// Parse the windows bugcheck codes and generate `src/bugcheck/bugcheck_codegen.rs`
@@ -31,56 +286,116 @@ fn gen_bugcheck() {
     .unwrap();
 }
 
-/// Parse the database for AMD cpus and generate `src/cpu/amd_codegen.rs`
-fn gen_amd_cpus() {
+/// Parse the database for AMD cpus and generate `src/cpu/amd_codegen.rs`, along with a map of the product IDs (OPNs)
+/// of each cpu to its name
+fn gen_amd_cpus(cpu_cache: &IntermediateCpuCache) {
     let destination = Path::new("src/cpu/").join("amd_codegen.rs");
     let mut generated_file = BufWriter::new(File::create(destination).unwrap());
-    let mut generated_map = phf_codegen::Map::new();
-    // there are a few duplicate entries, so this is quick and dirty way to do duplicate detection
-    let mut entries: HashSet<&str> = HashSet::new();
-    let cpu_cache = IntermediateCpuCache::new();
-    for cpu in cpu_cache.amd_cpus {
-        let mut attributes_map = phf_codegen::Map::new();
-        // if an entry wasn't already added
-        if entries.insert(cpu.name) {
-            for attr in cpu.attributes {
-                attributes_map.entry(format!("{:?}", attr.0), format!("{:?}", attr.1));
-            }
-            generated_map.entry(cpu.name, format!("{}", attributes_map.build()));
-        }
-    }
-    write!(
+    write_cpu_map(&mut generated_file, "AMD_CPUS", &cpu_cache.amd_cpus);
+    // a product ID attribute can list several IDs, sometimes annotated with their package, like
+    // `FP7:100-000000534 FP7r2: 100-000000617`, so anything too short to be an ID is skipped
+    let part_numbers = collect_part_numbers(
+        &cpu_cache.amd_cpus,
+        &["Product ID Boxed", "Product ID Tray", "Product ID MPK"],
+        |value| {
+            value
+                .split(|c: char| c.is_whitespace() || matches!(c, ',' | '/' | ':' | '(' | ')'))
+                .filter(|id| id.len() >= 8 && id.contains(|c: char| c.is_ascii_digit()))
+                .collect()
+        },
+    );
+    write_name_map(
         &mut generated_file,
-        "// This file was autogenerated by build.rs\n#[rustfmt::skip]\n#[allow(clippy::all)]\npub static AMD_CPUS: phf::Map<&'static str, phf::Map<&'static str, &'static str>> = {}",
-        generated_map.build()
-    )
-    .unwrap();
-    writeln!(&mut generated_file, ";").unwrap();
+        "Normalized AMD product IDs (OPNs), mapped to the name of the cpu in `AMD_CPUS`",
+        "AMD_PART_NUMBERS",
+        "&'static str",
+        part_numbers.iter().map(|(id, name)| (id.as_str(), *name)),
+    );
 }
 
-/// Parse the database for intel cpus and generate `src/cpu/intel_codegen.rs`
-fn gen_intel_cpus() {
+/// Parse the database for intel cpus and generate `src/cpu/intel_codegen.rs`, along with a map of the processor number
+/// of each cpu to its name
+fn gen_intel_cpus(cpu_cache: &IntermediateCpuCache) {
     let destination = Path::new("src/cpu/").join("intel_codegen.rs");
     let mut generated_file = BufWriter::new(File::create(destination).unwrap());
-    let mut generated_map = phf_codegen::Map::new();
-    // there are a few duplicate entries, so this is quick and dirty way to do duplicate detection
-    let mut entries: HashSet<String> = HashSet::new();
-    let cpu_cache = IntermediateCpuCache::new();
-    for cpu in cpu_cache.intel_cpus {
-        let mut attributes_map = phf_codegen::Map::new();
-        // if an entry wasn't already added
-        if entries.insert(cpu.name.to_string()) {
-            for attr in cpu.attributes {
-                attributes_map.entry(format!("{:?}", attr.0), format!("{:?}", attr.1));
+    write_cpu_map(&mut generated_file, "INTEL_CPUS", &cpu_cache.intel_cpus);
+    let part_numbers =
+        collect_part_numbers(&cpu_cache.intel_cpus, &["Processor Number"], |value| {
+            vec![value.trim()]
+        });
+    write_name_map(
+        &mut generated_file,
+        "Normalized intel processor numbers, mapped to the name of the cpu in `INTEL_CPUS`",
+        "INTEL_PART_NUMBERS",
+        "&'static str",
+        part_numbers.iter().map(|(id, name)| (id.as_str(), *name)),
+    );
+}
+
+/// Collect the part numbers of every cpu, normalized and paired with the name of the cpu. Part numbers are read from
+/// any attribute in `labels` and split apart with `split`
+fn collect_part_numbers<'a>(
+    cpus: &[Cpu<&'a str>],
+    labels: &[&str],
+    split: impl Fn(&'a str) -> Vec<&'a str>,
+) -> Vec<(String, &'a str)> {
+    let mut part_numbers = Vec::new();
+    for cpu in cpus {
+        for (label, value) in &cpu.attributes {
+            if labels.contains(label) {
+                for id in split(value) {
+                    part_numbers.push((normalize_part_number(id), cpu.name));
+                }
             }
-            generated_map.entry(cpu.name, format!("{}", attributes_map.build()));
         }
     }
-    write!(
+    part_numbers
+}
+
+/// Parse the database for qualcomm cpus and generate `src/cpu/qualcomm_codegen.rs`
+fn gen_qualcomm_cpus(cpu_cache: &IntermediateCpuCache) {
+    let destination = Path::new("src/cpu/").join("qualcomm_codegen.rs");
+    let mut generated_file = BufWriter::new(File::create(destination).unwrap());
+    write_cpu_map(
         &mut generated_file,
-        "// This file was autogenerated by build.rs\n#[rustfmt::skip]\n#[allow(clippy::all)]\npub static INTEL_CPUS: phf::Map<&'static str, phf::Map<&'static str, &'static str>> = {}",
+        "QUALCOMM_CPUS",
+        &cpu_cache.qualcomm_cpus,
+    );
+}
+
+/// Write a static map of cpu names to their attribute sections into `generated_file`. Sections are written as slices
+/// so that the order they're listed in is kept. There are a few duplicate entries, so only the first cpu with a name
+/// is kept
+fn write_cpu_map(generated_file: &mut impl Write, name: &str, cpus: &[Cpu<&str>]) {
+    let mut generated_map = phf_codegen::Map::new();
+    let mut entries: HashSet<&str> = HashSet::new();
+    for cpu in cpus {
+        if entries.insert(cpu.name) {
+            let sections: Vec<String> = cpu
+                .sections
+                .iter()
+                .map(|section| {
+                    let attributes: Vec<String> = section
+                        .attributes
+                        .iter()
+                        .map(|(label, value)| format!("({label:?}, {value:?})"))
+                        .collect();
+                    format!("({:?}, &[{}])", section.name, attributes.join(", "))
+                })
+                .collect();
+            generated_map.entry(cpu.name, format!("&[{}]", sections.join(", ")));
+        }
+    }
+    writeln!(
+        generated_file,
+        "// This file was autogenerated by build.rs\n#[rustfmt::skip]\n#[allow(clippy::all)]\npub static {name}: phf::Map<&'static str, &'static [(&'static str, &'static [(&'static str, &'static str)])]> = {};",
         generated_map.build()
     )
     .unwrap();
-    writeln!(&mut generated_file, ";").unwrap();
+}
+
+/// Uppercase a part number and remove any whitespace, intel lists some processor numbers with a space before the
+/// version, like `E5-2680 v4`, and others without. This should match the normalization done by `CpuCache::find_by_part`
+fn normalize_part_number(id: &str) -> String {
+    id.split_whitespace().collect::<String>().to_uppercase()
 }
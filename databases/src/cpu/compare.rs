@@ -0,0 +1,214 @@
+//! Comparing several cpus side by side, like the comparison pages on ARK
+use super::{Cpu, CpuCache, CpuLookupError, CpuSpec};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Reads a numeric field out of a spec
+type SpecField = fn(&CpuSpec) -> Option<f64>;
+
+/// The spec fields that deltas are calculated for, and how to read them
+const DELTA_FIELDS: [(&str, SpecField); 8] = [
+    ("cores", |s| s.cores.map(f64::from)),
+    ("threads", |s| s.threads.map(f64::from)),
+    ("base_clock_mhz", |s| s.base_clock_mhz.map(f64::from)),
+    ("boost_clock_mhz", |s| s.boost_clock_mhz.map(f64::from)),
+    ("l1_cache_kib", |s| s.l1_cache_kib.map(f64::from)),
+    ("l2_cache_kib", |s| s.l2_cache_kib.map(f64::from)),
+    ("l3_cache_kib", |s| s.l3_cache_kib.map(f64::from)),
+    ("tdp_watts", |s| s.tdp_watts.map(f64::from)),
+];
+
+/// Several cpus lined up against each other. Every list of values has one entry per cpu, in the same order as `cpus`
+#[derive(Clone, Debug, Serialize)]
+pub struct CpuComparison {
+    /// The names of the cpus that were compared, the first cpu is the baseline for the deltas
+    pub cpus: Vec<&'static str>,
+    /// Every attribute listed by any of the cpus, sorted by name
+    pub attributes: Vec<AttributeRow>,
+    /// The numeric spec fields, and how far each cpu is from the first
+    pub deltas: Vec<SpecDelta>,
+    /// Features that some, but not all of the cpus have
+    pub exclusive_features: Vec<ExclusiveFeature>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AttributeRow {
+    /// The name of the attribute, without footnote markers
+    pub attribute: String,
+    /// The raw value for each cpu, `None` if the cpu doesn't list the attribute
+    pub values: Vec<Option<&'static str>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SpecDelta {
+    /// The name of the field in [CpuSpec]
+    pub field: &'static str,
+    pub values: Vec<Option<f64>>,
+    /// The difference between each cpu and the first cpu, `None` if either doesn't list the field
+    pub deltas: Vec<Option<f64>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExclusiveFeature {
+    pub feature: String,
+    /// The names of the cpus that have the feature
+    pub cpus: Vec<&'static str>,
+}
+
+impl CpuCache {
+    /// Look up every cpu in `inputs` with [CpuCache::find], and compare them. The first error hit while looking up the
+    /// cpus is returned
+    #[tracing::instrument(name = "cpu_compare", skip(self))]
+    pub fn compare(&self, inputs: &[&str]) -> Result<CpuComparison, CpuLookupError> {
+        let cpus: Vec<Cpu<&'static str>> = inputs
+            .iter()
+            .map(|input| self.find(input))
+            .collect::<Result<_, _>>()?;
+        Ok(compare_cpus(&cpus))
+    }
+}
+
+fn compare_cpus(cpus: &[Cpu<&'static str>]) -> CpuComparison {
+    let mut rows: BTreeMap<&str, Vec<Option<&'static str>>> = BTreeMap::new();
+    for (i, cpu) in cpus.iter().enumerate() {
+        for (label, value) in &cpu.attributes {
+            rows.entry(label).or_insert_with(|| vec![None; cpus.len()])[i] = Some(value);
+        }
+    }
+
+    let deltas = DELTA_FIELDS
+        .iter()
+        .map(|(field, read)| {
+            let values: Vec<Option<f64>> = cpus.iter().map(|cpu| read(&cpu.spec)).collect();
+            let deltas = values
+                .iter()
+                .map(|value| Some(value.as_ref()? - values[0]?))
+                .collect();
+            SpecDelta {
+                field,
+                values,
+                deltas,
+            }
+        })
+        .collect();
+
+    let features: Vec<BTreeSet<String>> = cpus.iter().map(features).collect();
+    let every_feature: BTreeSet<&String> = features.iter().flatten().collect();
+    let exclusive_features = every_feature
+        .into_iter()
+        .filter_map(|feature| {
+            let have: Vec<&'static str> = cpus
+                .iter()
+                .zip(&features)
+                .filter(|(_, f)| f.contains(feature))
+                .map(|(cpu, _)| cpu.name)
+                .collect();
+            (have.len() < cpus.len()).then(|| ExclusiveFeature {
+                feature: feature.clone(),
+                cpus: have,
+            })
+        })
+        .collect();
+
+    CpuComparison {
+        cpus: cpus.iter().map(|cpu| cpu.name).collect(),
+        attributes: rows
+            .into_iter()
+            .map(|(attribute, values)| AttributeRow {
+                attribute: attribute.to_string(),
+                values,
+            })
+            .collect(),
+        deltas,
+        exclusive_features,
+    }
+}
+
+/// The features a cpu has. Intel lists features as attributes with a value of "Yes" or "No", AMD does the same with
+/// "Available" or "Not Available", and also lists some in "Supported Technologies", like "AMD EXPO™ Technology , AES"
+fn features(cpu: &Cpu<&'static str>) -> BTreeSet<String> {
+    let mut features = BTreeSet::new();
+    for (&label, value) in &cpu.attributes {
+        match value.trim() {
+            "Yes" | "Available" => {
+                features.insert(label.to_string());
+            }
+            technologies if label == "Supported Technologies" => {
+                features.extend(
+                    technologies
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(String::from),
+                );
+            }
+            _ => {}
+        }
+    }
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cpu::{CpuCache, CpuLookupError};
+
+    #[test]
+    fn compare_cpus() {
+        let cache = CpuCache::new();
+        let comparison = cache
+            .compare(&[
+                "Intel(R) Core(TM) i5-9400F CPU @ 2.90GHz",
+                "Intel(R) Core(TM) i7-9700K CPU @ 3.60GHz",
+            ])
+            .unwrap();
+        assert_eq!(
+            comparison.cpus,
+            [
+                "Intel® Core™ i5-9400F Processor",
+                "Intel® Core™ i7-9700K Processor"
+            ]
+        );
+        // the ARK exports line up, so most attributes are listed for both
+        let both = comparison
+            .attributes
+            .iter()
+            .filter(|row| row.values.iter().all(Option::is_some))
+            .count();
+        assert!(both > comparison.attributes.len() / 2);
+        let cores = comparison
+            .deltas
+            .iter()
+            .find(|d| d.field == "cores")
+            .unwrap();
+        assert_eq!(cores.values, [Some(6.0), Some(8.0)]);
+        assert_eq!(cores.deltas, [Some(0.0), Some(2.0)]);
+        // the 9400F has no integrated graphics, and isn't unlocked
+        let has_graphics = comparison
+            .exclusive_features
+            .iter()
+            .find(|f| f.feature == "Intel® Quick Sync Video")
+            .unwrap();
+        assert_eq!(has_graphics.cpus, ["Intel® Core™ i7-9700K Processor"]);
+
+        // cpus from different vendors can still be compared by their spec
+        let comparison = cache
+            .compare(&[
+                "Intel(R) Core(TM) i5-9400F CPU @ 2.90GHz",
+                "AMD Ryzen 5 3600",
+            ])
+            .unwrap();
+        let threads = comparison
+            .deltas
+            .iter()
+            .find(|d| d.field == "threads")
+            .unwrap();
+        assert_eq!(threads.deltas, [Some(0.0), Some(6.0)]);
+
+        assert_eq!(
+            cache
+                .compare(&["AMD Ryzen 5 3600", "Apple M2"])
+                .unwrap_err(),
+            CpuLookupError::UnknownVendor(String::from("Apple M2"))
+        );
+    }
+}
@@ -1,13 +1,34 @@
 mod amd_codegen;
+mod compare;
 mod intel_codegen;
+mod qualcomm_codegen;
+mod search;
+mod spec;
+mod suggest;
+mod vendor;
 
-use amd_codegen::AMD_CPUS;
-use intel_codegen::INTEL_CPUS;
-use nom::bytes::complete::{take_until, take_while};
+use amd_codegen::{AMD_CPUS, AMD_PART_NUMBERS};
+pub use compare::{AttributeRow, CpuComparison, ExclusiveFeature, SpecDelta};
+use intel_codegen::{INTEL_CPUS, INTEL_PART_NUMBERS};
+use nom::bytes::complete::take_till;
+use nom::character::complete::digit1;
+pub use parsing::cpu::AttributeSection;
 use phf::Map;
+use qualcomm_codegen::QUALCOMM_CPUS;
+pub use search::{CatalogEntry, CpuFilter, CpuSortKey, SearchResults, SortOrder};
 use serde::Serialize;
+pub use spec::{CpuSpec, LaunchDate, Segment};
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::OnceLock;
+use suggest::SuggestIndex;
 use tracing::{debug, error};
+pub use vendor::CpuVendor;
+
+/// The lowest confidence a match can have without being rejected by a strict lookup. Any candidate with a matching
+/// prefix and suffix meets this, regardless of how many tags it shares with the input
+pub const STRICT_CONFIDENCE_THRESHOLD: f32 = 0.75;
 
 /// A generic representation of a cpu. T is the string type, there are massive gains by using zero copy for the intel cpu database, but that's a lot more work
 /// for the amd CPU database.
@@ -17,10 +38,99 @@ use tracing::{debug, error};
 pub struct Cpu<T> {
     /// Something like "Intel core i5-1234 processor"
     pub name: T,
-    /// A list of attributes, examples might include a core count of 8, or whether or not a certain feature is enabled
+    /// A list of attributes, examples might include a core count of 8, or whether or not a certain feature is enabled.
+    /// These are the same attributes as in `sections`, indexed by label for lookups, so they aren't serialized
+    #[serde(skip)]
     pub attributes: HashMap<T, T>,
+    /// The attributes, grouped into sections and kept in the order the vendor lists them
+    pub sections: Vec<AttributeSection<T>>,
+    /// The attributes that are common to every cpu, read into a shape that's the same across vendors
+    pub spec: CpuSpec,
+}
+
+/// A cpu that might be the one that was searched for, returned by [CpuCache::find_candidates]
+#[derive(Clone, Debug, Serialize)]
+pub struct CpuCandidate {
+    pub cpu: Cpu<&'static str>,
+    /// A higher score indicates a closer match, candidates are ranked by this
+    pub score: i32,
+    /// How likely it is that this is the cpu that was searched for, from `0.0` to `1.0`.
+    /// A candidate with a matching prefix and suffix starts at `0.75`, and the rest is made up by the share of the
+    /// input's tags that the candidate also has. Every mismatch between the prefix or suffix halves the confidence
+    pub confidence: f32,
+    /// How the score was reached
+    pub explanation: ScoreExplanation,
+}
+
+/// A breakdown of how a candidate was scored against the input
+#[derive(Clone, Debug, Serialize)]
+pub struct ScoreExplanation {
+    /// The model number read from the input, every candidate has the same model
+    pub model: String,
+    /// The prefix read from the input
+    pub input_prefix: String,
+    /// The prefix of the candidate
+    pub candidate_prefix: String,
+    /// The points docked if the prefixes don't match
+    pub prefix_score: i32,
+    /// The suffix read from the input
+    pub input_suffix: String,
+    /// The suffix of the candidate
+    pub candidate_suffix: String,
+    /// The points docked if the suffixes don't match
+    pub suffix_score: i32,
+    /// The tags that both the input and the candidate have
+    pub matched_tags: Vec<String>,
+    /// The points given for matching tags
+    pub tag_score: i32,
+}
+
+/// The reasons a cpu lookup can fail
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CpuLookupError {
+    /// The vendor couldn't be worked out from the input, contains the input
+    UnknownVendor(String),
+    /// The vendor was recognized, but there's no database for it
+    UnsupportedVendor(CpuVendor),
+    /// A model number couldn't be read from the input, contains the input
+    NoModel(String),
+    /// No cpus were found with the same model number as the input, contains the model number
+    NoMatch(String),
+    /// No cpus have the part number that was searched for, contains the part number
+    UnknownPart(String),
+}
+
+impl CpuLookupError {
+    /// A short, stable name for the error, intended for machine consumption
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::UnknownVendor(_) => "unknown_vendor",
+            Self::UnsupportedVendor(_) => "unsupported_vendor",
+            Self::NoModel(_) => "no_model",
+            Self::NoMatch(_) => "no_match",
+            Self::UnknownPart(_) => "unknown_part",
+        }
+    }
+}
+
+impl fmt::Display for CpuLookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVendor(input) => {
+                write!(f, "the vendor of the cpu {input:?} couldn't be determined")
+            }
+            Self::UnsupportedVendor(vendor) => write!(f, "{vendor} cpus aren't supported"),
+            Self::NoModel(input) => write!(f, "no model number was found in {input:?}"),
+            Self::NoMatch(model) => write!(f, "no cpus were found with the model {model:?}"),
+            Self::UnknownPart(part) => {
+                write!(f, "no cpus were found with the part number {part:?}")
+            }
+        }
+    }
 }
 
+impl std::error::Error for CpuLookupError {}
+
 // TODO: &'static str all of this
 #[derive(PartialEq, Clone)]
 struct IndexEntry {
@@ -29,9 +139,11 @@ struct IndexEntry {
     model: String,
     /// An identifier applied directly to the beginning of the processor number, like:
     /// - `i7-` in `i7-7600`
+    /// - `G` in `G5400`
     prefix: String,
     /// Identifiers applied to the end of the processor number, like:
     /// - `F` in `i5-11400F`
+    /// - `v4` in `E5-2680 v4`
     suffix: String,
     /// Similar to modifiers, but they're not directly a part of the processor, like:
     /// `PRO` in `Ryzen 5 PRO 5600`
@@ -40,155 +152,244 @@ struct IndexEntry {
     key: String,
 }
 
-#[derive(Clone)]
+/// Index entries grouped by their model, so that finding every cpu with the same model as an input is a single lookup
+type ModelIndex = HashMap<String, Vec<IndexEntry>>;
+
+/// The attributes of a cpu in a database, as a list of section names paired with the labels and values in that section
+type CpuSections = &'static [(&'static str, &'static [(&'static str, &'static str)])];
+
+/// A database generated by `build.rs`, cpu names mapped to their attributes
+type CpuDatabase = Map<&'static str, CpuSections>;
+
+/// The index is never modified after creation, so the cache is intended to be shared between threads behind an `Arc`
 pub struct CpuCache {
-    intel_index: Vec<IndexEntry>,
-    amd_index: Vec<IndexEntry>,
+    intel_index: ModelIndex,
+    amd_index: ModelIndex,
+    qualcomm_index: ModelIndex,
+    /// Every cpu in every database, sorted by name. Reading the spec of every cpu is slow compared to indexing, so
+    /// this is only built the first time the catalog is searched
+    catalog: OnceLock<Vec<CatalogEntry>>,
+    suggest_index: SuggestIndex,
 }
 
 impl CpuCache {
     /// Create a new cache and parse the cpu databases into memory
     pub fn new() -> Self {
-        let mut intel_index: Vec<IndexEntry> = Vec::with_capacity(512);
-        for name in INTEL_CPUS.keys() {
-            match generate_index_entry(name) {
-                Ok(idx) => {
-                    intel_index.push(idx);
-                }
-                Err(e) => {
-                    error!("index will not be complete because generation generation failed for cpu: {:?} with error {:?}", name, e);
-                }
-            }
-        }
+        let intel_index = index_database(&INTEL_CPUS);
         debug!("Index generated for Intel CPUs");
-        let mut amd_index: Vec<IndexEntry> = Vec::with_capacity(2048);
-        for name in AMD_CPUS.keys() {
-            match generate_index_entry(name) {
-                Ok(idx) => {
-                    amd_index.push(idx);
-                }
-                Err(e) => {
-                    error!("index will not be complete because generation generation failed for cpu: {:?} with error {:?}", name, e);
-                }
-            }
-        }
+        let amd_index = index_database(&AMD_CPUS);
         debug!("Index generated for AMD CPUs");
-        // because of the way memory allocations for vectors are done, over time, a lot of empty elements can get pre-allocated.
-        // remove those now
-        intel_index.shrink_to_fit();
-        amd_index.shrink_to_fit();
+        let qualcomm_index = index_database(&QUALCOMM_CPUS);
+        debug!("Index generated for Qualcomm CPUs");
+        let suggest_index = SuggestIndex::new(&[&INTEL_CPUS, &AMD_CPUS, &QUALCOMM_CPUS]);
+        debug!("Suggestion index generated for every CPU");
 
         Self {
             intel_index,
             amd_index,
+            qualcomm_index,
+            catalog: OnceLock::new(),
+            suggest_index,
+        }
+    }
+
+    /// Look up a cpu by its exact part number, either an AMD product ID (OPN) like `100-000001683`, or an intel
+    /// processor number like `i5-9400F`. Unlike [CpuCache::find], no fuzzy matching is done, although case and
+    /// whitespace are ignored
+    #[tracing::instrument(name = "cpu_part_lookup", skip(self))]
+    pub fn find_by_part(&self, part_number: &str) -> Result<Cpu<&'static str>, CpuLookupError> {
+        let part_number = part_number
+            .split_whitespace()
+            .collect::<String>()
+            .to_uppercase();
+        let entry = if let Some(name) = AMD_PART_NUMBERS.get(&part_number) {
+            AMD_CPUS.get_entry(name)
+        } else if let Some(name) = INTEL_PART_NUMBERS.get(&part_number) {
+            INTEL_CPUS.get_entry(name)
+        } else {
+            None
+        };
+        let (name, attributes) = entry.ok_or(CpuLookupError::UnknownPart(part_number))?;
+        Ok(Cpu::from((*name, attributes)))
+    }
+
+    /// Get the index and database for a vendor, or `None` if there's no database for them. Supporting a new vendor
+    /// means generating its database in `build.rs`, indexing it in [CpuCache::new], and returning it here
+    fn database(&self, vendor: CpuVendor) -> Option<(&ModelIndex, &'static CpuDatabase)> {
+        match vendor {
+            CpuVendor::Intel => Some((&self.intel_index, &INTEL_CPUS)),
+            CpuVendor::Amd => Some((&self.amd_index, &AMD_CPUS)),
+            CpuVendor::Qualcomm => Some((&self.qualcomm_index, &QUALCOMM_CPUS)),
+            CpuVendor::Hygon | CpuVendor::Via | CpuVendor::Zhaoxin => None,
         }
     }
 
     /// Given a string that contains the inexact name of a cpu, try to find the best fit
     /// and return it. For example, it might take an input of "AMD Ryzen 5 3600 6-Core Processor",
     /// and return the entry with a `name` of "AMD Ryzen™ 5 3600".
-    ///
-    /// A mutable reference is required so that the comparison cache can be shared between calls
     #[tracing::instrument(name = "cpu_lookup", skip(self))]
-    pub fn find<'a>(
-        &'a mut self,
-        input: &'a str,
-    ) -> Result<Cpu<&'static str>, Box<dyn std::error::Error + 'a>> {
-        let index = if input.contains("AMD") {
-            &self.amd_index
-        } else {
-            &self.intel_index
-        };
-        let idx_for_input = generate_index_entry(input)?;
+    pub fn find(&self, input: &str) -> Result<Cpu<&'static str>, CpuLookupError> {
+        let mut candidates = self.find_candidates(input, 1)?;
+        Ok(candidates.remove(0).cpu)
+    }
+
+    /// Like [CpuCache::find], but return up to `count` of the closest fits, ranked from best to worst. Only cpus from
+    /// the same vendor with the same model number as the input are considered, so an error is returned if there are none
+    #[tracing::instrument(name = "cpu_candidates_lookup", skip(self))]
+    pub fn find_candidates(
+        &self,
+        input: &str,
+        count: usize,
+    ) -> Result<Vec<CpuCandidate>, CpuLookupError> {
+        let vendor = CpuVendor::detect(input)
+            .ok_or_else(|| CpuLookupError::UnknownVendor(input.to_string()))?;
+        let (index, database) = self
+            .database(vendor)
+            .ok_or(CpuLookupError::UnsupportedVendor(vendor))?;
+        let idx_for_input = generate_index_entry(input).map_err(|e| {
+            error!(
+                "When searching for cpu {:?}, an index entry couldn't be generated: {:?}",
+                input, e
+            );
+            CpuLookupError::NoModel(input.to_string())
+        })?;
         // first look for an index entry that has an exact match for the processor model number
-        let similar_cpus = index.iter().filter(|idx| idx.model == idx_for_input.model);
-        // now find the closest fit among all similar cpus
-        // a higher score indicates a closer match
-        let mut best_score = -100;
-        let mut best_idx_match: Option<&IndexEntry> = None;
-        for idx_entry in similar_cpus {
-            let mut score: i32 = 0;
-            // if the prefix doesn't match, dock points
-            if idx_for_input.prefix != idx_entry.prefix {
-                score -= 10;
-            }
-            // if the suffix doesn't match, dock points
-            if idx_for_input.suffix != idx_entry.suffix {
-                score -= 10;
-            }
-            // for every matching tag that both entries have, give points
-            // points are not currently docked if the entry is missing tags that the input has
-            for tag in &idx_for_input.tags {
-                if idx_entry.tags.contains(tag) {
-                    score += 5;
+        let Some(similar_cpus) = index.get(&idx_for_input.model) else {
+            error!("When searching for cpu {:?}, no cpus were found with a matching model number of: {:?}", input, idx_for_input.model);
+            return Err(CpuLookupError::NoMatch(idx_for_input.model));
+        };
+        // now rank all similar cpus by how closely they fit
+        let mut scored: Vec<(&IndexEntry, ScoreExplanation)> = similar_cpus
+            .iter()
+            .map(|idx_entry| (idx_entry, score_entry(&idx_for_input, idx_entry)))
+            .collect();
+        // if several entries are tied, the one with the fewest tags is picked, because extra tags are usually bundle
+        // details, like "FX-8350 with Wraith cooler"
+        scored.sort_by_key(|(idx_entry, explanation)| {
+            (Reverse(explanation.score()), idx_entry.tags.len())
+        });
+        Ok(scored
+            .into_iter()
+            .take(count)
+            .map(|(idx_entry, explanation)| {
+                let entry = database.get_entry(&idx_entry.key).unwrap();
+                let cpu = Cpu::from((*entry.0, entry.1));
+                CpuCandidate {
+                    cpu,
+                    score: explanation.score(),
+                    confidence: explanation.confidence(idx_for_input.tags.len()),
+                    explanation,
                 }
-            }
-            // update the best fit if a better fit was found
-            if score > best_score {
-                best_score = score;
-                best_idx_match = Some(idx_entry);
-            }
+            })
+            .collect())
+    }
+}
+
+impl Default for CpuCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScoreExplanation {
+    /// The total score, a higher score indicates a closer match
+    pub fn score(&self) -> i32 {
+        self.prefix_score + self.suffix_score + self.tag_score
+    }
+
+    /// Normalize the score into a confidence from `0.0` to `1.0`, given how many tags the input had
+    fn confidence(&self, input_tag_count: usize) -> f32 {
+        let tag_share = if input_tag_count == 0 {
+            1.0
+        } else {
+            self.matched_tags.len() as f32 / input_tag_count as f32
+        };
+        let mut confidence = 0.75 + 0.25 * tag_share;
+        if self.prefix_score < 0 {
+            confidence /= 2.0;
+        }
+        if self.suffix_score < 0 {
+            confidence /= 2.0;
         }
-        // let cpus: &Vec<Cpu<_>> = if input.contains("AMD") {
-        //     &self.amd_cpus
-        // } else {
-        //     &self.intel_cpus
-        // };
-        match best_idx_match {
-            None => {
-                error!("When searching for cpu {:?}, no cpus were found with a matching model number of: {:?}", input, idx_for_input.model);
-                Err(Box::from("No close matches found"))
+        confidence
+    }
+}
+
+/// Generate an index entry for every cpu in a database
+fn index_database(database: &CpuDatabase) -> ModelIndex {
+    let mut index: ModelIndex = HashMap::with_capacity(database.len());
+    for name in database.keys() {
+        match generate_index_entry(name) {
+            Ok(idx) => {
+                index.entry(idx.model.clone()).or_default().push(idx);
             }
-            Some(idx_entry) => {
-                if input.contains("AMD") {
-                    let entry = AMD_CPUS.get_entry(&idx_entry.key).unwrap();
-                    Ok(Cpu::from((*entry.0, entry.1)))
-                } else {
-                    let entry = INTEL_CPUS.get_entry(&idx_entry.key).unwrap();
-                    Ok(Cpu::from((*entry.0, entry.1)))
-                }
-                // // intel requires some work to un-zerocopy data
-                // let found_cpu = &self.intel_cpus[idx_entry.key];
-                // return Ok(Cpu {
-                //     name: found_cpu.name.to_string(),
-                //     attributes: found_cpu
-                //         .attributes
-                //         .iter()
-                //         .map(|(k, v)| (k.to_string(), v.to_string()))
-                //         .collect(),
-                // });
+            Err(e) => {
+                error!("index will not be complete because generation generation failed for cpu: {:?} with error {:?}", name, e);
             }
         }
     }
+    // because of the way memory allocations for vectors are done, over time, a lot of empty elements can get pre-allocated.
+    // remove those now
+    index.shrink_to_fit();
+    index
 }
 
-impl Default for CpuCache {
-    fn default() -> Self {
-        Self::new()
+/// Score how closely an index entry fits the index entry generated for the input
+fn score_entry(input: &IndexEntry, entry: &IndexEntry) -> ScoreExplanation {
+    // if the prefix or suffix doesn't match, dock points
+    let prefix_score = if input.prefix == entry.prefix { 0 } else { -10 };
+    let suffix_score = if input.suffix == entry.suffix { 0 } else { -10 };
+    // for every matching tag that both entries have, give points
+    // points are not currently docked if the entry is missing tags that the input has
+    let mut matched_tags: Vec<String> = input
+        .tags
+        .iter()
+        .filter(|tag| entry.tags.contains(*tag))
+        .cloned()
+        .collect();
+    matched_tags.sort();
+    ScoreExplanation {
+        model: input.model.clone(),
+        input_prefix: input.prefix.clone(),
+        candidate_prefix: entry.prefix.clone(),
+        prefix_score,
+        input_suffix: input.suffix.clone(),
+        candidate_suffix: entry.suffix.clone(),
+        suffix_score,
+        tag_score: 5 * matched_tags.len() as i32,
+        matched_tags,
     }
 }
 
 /// Take the input model name, and try to parse it into an [IndexEntry]
 fn generate_index_entry(name: &str) -> Result<IndexEntry, Box<dyn std::error::Error + '_>> {
-    let model_token = find_model(name);
-    // find the prefix, if one exists
-    let prefix_combinator: (&str, &str) =
-        match take_until::<_, _, nom::error::Error<_>>("-")(model_token.as_str()) {
-            Ok(p) => p,
-            Err(_) => {
-                // assume there's no prefix
-                ("", &model_token)
-            }
-        };
-    let model_combinator: (&str, &str) = match take_while::<_, _, nom::error::Error<_>>(
-        |c: char| c.is_ascii_digit(),
-    )(prefix_combinator.1)
-    {
+    // case is normalized so that inputs like "amd ryzen 5 3600x" still match
+    let model_token = find_model(name).to_uppercase();
+    // a dash separates a series from the model, like `I5-` in `I5-9400F`, `W-` in `W-2145`, or `X5-` in `X5-Z8350`.
+    // dashes after the model number, like in `6518P-B`, are a part of the suffix
+    let series_end = model_token
+        .rmatch_indices('-')
+        .map(|(i, _)| i + 1)
+        .find(|i| model_token[*i..].contains(|c: char| c.is_ascii_digit()));
+    let (series, after_series) = model_token.split_at(series_end.unwrap_or(0));
+    // letters directly before the model number are also a part of the prefix, like `G` in `G5400`
+    let (after_letters, letters) =
+        take_till::<_, _, nom::error::Error<_>>(|c: char| c.is_ascii_digit())(after_series)
+            .unwrap_or((after_series, ""));
+    let (suffix, model) = match digit1::<_, nom::error::Error<_>>(after_letters) {
         Ok(m) => m,
         Err(_) => {
             return Err(Box::from(format!("index generation failed for cpu {:?} because no base 10 digits were found after the prefix", name)));
         }
     };
+    // xeons can have a version in a separate token, like `v4` in `E5-2680 v4`. It's treated as a part of the suffix,
+    // because different versions are entirely different cpus
+    let version = name
+        .split(' ')
+        .find(|t| {
+            t.len() > 1 && t.starts_with(['v', 'V']) && t[1..].chars().all(|c| c.is_ascii_digit())
+        })
+        .unwrap_or_default();
     // tags are considered anything *but* the name, and a few keywords
     let blacklist = ["Intel", "AMD", "Processor", name];
     let mut tags: HashSet<String> = HashSet::new();
@@ -197,10 +398,10 @@ fn generate_index_entry(name: &str) -> Result<IndexEntry, Box<dyn std::error::Er
     }
 
     Ok(IndexEntry {
-        model: String::from(model_combinator.0),
-        prefix: String::from(prefix_combinator.0),
-        // just whatever is leftover after the combinator
-        suffix: String::from(model_combinator.1),
+        model: String::from(model),
+        prefix: format!("{series}{letters}"),
+        // just whatever is leftover after the model number
+        suffix: format!("{suffix}{}", version.to_uppercase()),
         tags,
         key: String::from(name),
     })
@@ -211,6 +412,9 @@ fn generate_index_entry(name: &str) -> Result<IndexEntry, Box<dyn std::error::Er
 /// This function does return the whole token associated with a model, so prefixes and suffixes
 /// are included
 fn find_model(input: &str) -> String {
+    // trademark symbols are sometimes attached directly to the model, like `FX(tm)-8350`, and the parentheses
+    // would count against the token
+    let input = &remove_trademarks(input);
     let mut best_fit = "";
     let mut high_score: isize = -10;
     for token in input.split(' ') {
@@ -235,6 +439,42 @@ fn find_model(input: &str) -> String {
         }
     }
 
+    // Core Ultra and Core 3/5/7 cpus report their tier as its own token, like `Core Ultra 7 155H` or `Core 7 150U`
+    // (the database adds "processor" before the model). The tier is taped to the model, like `Ultra7-155H`, so that
+    // the two lineups don't match each other or older cpus that share a model number
+    {
+        let tokens: Vec<&str> = input.split(' ').collect();
+        let tier = tokens.windows(2).find_map(|pair| {
+            let is_tier = matches!(pair[1], "3" | "5" | "7" | "9");
+            match pair[0] {
+                "Ultra" if is_tier => Some(format!("Ultra{}", pair[1])),
+                "Core" if is_tier => Some(format!("Core{}", pair[1])),
+                _ => None,
+            }
+        });
+        if let Some(tier) = tier {
+            return format!("{}-{}", tier, best_fit);
+        }
+    }
+
+    // Snapdragon X part numbers are written as `X1E-78-100` by the database, but as `X1E78100` by the WMI. Both are
+    // rewritten to `X1E-78100`, so that the tier (`E` for Elite, `P` for Plus, or nothing) ends up in the prefix
+    {
+        if input.contains("Snapdragon") {
+            if let Some(part) = best_fit.strip_prefix("X1") {
+                let part = part.replace('-', "");
+                let (tier, number) =
+                    part.split_at(part.find(|c: char| c.is_ascii_digit()).unwrap_or(0));
+                if tier.len() <= 1
+                    && !number.is_empty()
+                    && number.chars().all(|c| c.is_ascii_digit())
+                {
+                    return format!("X1{}-{}", tier, number);
+                }
+            }
+        }
+    }
+
     // Ryzen PRO cpus have the same model, they're a different lineup though.
     // This is handled by taping PRO to the model
     {
@@ -258,6 +498,15 @@ fn find_model(input: &str) -> String {
     best_fit.to_string()
 }
 
+/// Remove every trademark and registered trademark symbol from the input
+fn remove_trademarks(input: &str) -> String {
+    let mut output = input.to_string();
+    for mark in ["(tm)", "(TM)", "(R)", "(r)", "™", "®"] {
+        output = output.replace(mark, "");
+    }
+    output
+}
+
 /// This function tries to determine the likelihood that the given token is the "model" of a cpu.
 /// For example, with the string "Intel(R) Core(TM) i5-9400F CPU @ 2.90GHz", the token "i5-9400F"
 /// would be given the highest score, while tokens like "Intel(R)" would ideally be given a significantly lower score
@@ -278,26 +527,33 @@ fn calculate_model_score(token: &str) -> isize {
     score
 }
 
-impl From<(&'static str, &Map<&str, &str>)> for Cpu<&'static str> {
-    fn from(value: (&'static str, &Map<&str, &str>)) -> Self {
+impl From<(&'static str, &CpuSections)> for Cpu<&'static str> {
+    fn from(value: (&'static str, &CpuSections)) -> Self {
         let mut attributes = HashMap::new();
-        for a in value.1.into_iter() {
-            attributes.insert(*a.0, *a.1);
+        let mut sections = Vec::with_capacity(value.1.len());
+        for (name, section) in value.1.iter() {
+            attributes.extend(section.iter().copied());
+            sections.push(AttributeSection {
+                name: *name,
+                attributes: section.to_vec(),
+            });
         }
         Self {
             name: value.0,
+            spec: CpuSpec::from_attributes(&attributes),
             attributes,
+            sections,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::CpuCache;
+    use super::{CpuCache, CpuLookupError, CpuVendor, Segment, STRICT_CONFIDENCE_THRESHOLD};
 
     #[test]
     fn search_resilience() {
-        let mut cache = CpuCache::new();
+        let cache = CpuCache::new();
         // on the left is the name stored in the cache, on the right is the name collected from WMI data
         // these test cases should be filled out as time goes on with failing test cases
         // any test cases commented out currently fail
@@ -333,11 +589,236 @@ mod tests {
                 "Intel® Core™ i7-620M Processor",
                 "Intel(R) Core(TM) i7 CPU M 620 @ 2.67Ghz",
             ),
+            (
+                "Intel® Pentium® Gold G5400 Processor",
+                "Intel(R) Pentium(R) Gold G5400 CPU @ 3.70GHz",
+            ),
+            (
+                "Intel® Celeron® Processor N4020",
+                "Intel(R) Celeron(R) N4020 CPU @ 1.10GHz",
+            ),
+            (
+                "Intel® Celeron® Processor J4125",
+                "Intel(R) Celeron(R) J4125 CPU @ 2.00GHz",
+            ),
+            (
+                "Intel® Xeon® Processor E5-2680 v4",
+                "Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40GHz",
+            ),
+            (
+                "Intel® Xeon® Processor E5-2680 v2",
+                "Intel(R) Xeon(R) CPU E5-2680 v2 @ 2.80GHz",
+            ),
+            (
+                "Intel® Xeon® Processor E5-2680",
+                "Intel(R) Xeon(R) CPU E5-2680 0 @ 2.70GHz",
+            ),
+            (
+                "Intel Atom® x5-Z8350 Processor",
+                "Intel(R) Atom(TM) x5-Z8350  CPU @ 1.44GHz",
+            ),
+            (
+                "Intel® Xeon® W-2145 Processor",
+                "Intel(R) Xeon(R) W-2145 CPU @ 3.70GHz",
+            ),
+            (
+                "Intel® Xeon® Processor D-1541",
+                "Intel(R) Xeon(R) CPU D-1541 @ 2.10GHz",
+            ),
+            (
+                "Intel® Core™ Ultra 7 processor 155H",
+                "Intel(R) Core(TM) Ultra 7 155H",
+            ),
+            (
+                "Intel® Core™ Ultra 5 processor 125U",
+                "Intel(R) Core(TM) Ultra 5 125U",
+            ),
+            ("Intel® Core™ 7 processor 150U", "Intel(R) Core(TM) 7 150U"),
+            (
+                "Intel® Core™ Ultra 5 Processor 322",
+                "Intel(R) Core(TM) Ultra 5 322",
+            ),
+            ("FX-8350", "AMD FX(tm)-8350 Eight-Core Processor"),
+            ("FX-6300", "AMD FX(tm)-6300 Six-Core Processor"),
+            (
+                "AMD Athlon™ Silver 3050U",
+                "AMD Athlon Silver 3050U with Radeon Graphics",
+            ),
+            (
+                "AMD Athlon™ 3000G",
+                "AMD Athlon 3000G with Radeon Vega Graphics",
+            ),
+            (
+                "AMD Athlon™ 200GE",
+                "AMD Athlon 200GE with Radeon Vega Graphics",
+            ),
+            (
+                "AMD Ryzen™ Threadripper™ PRO 5995WX",
+                "AMD Ryzen Threadripper PRO 5995WX 64-Cores",
+            ),
+            (
+                "AMD Ryzen™ Threadripper™ 3990X",
+                "AMD Ryzen Threadripper 3990X 64-Core Processor",
+            ),
+            (
+                "AMD Ryzen™ 7 5800X3D",
+                "AMD Ryzen 7 5800X3D 8-Core Processor",
+            ),
+            ("AMD Ryzen™ 7 5800X", "AMD Ryzen 7 5800X 8-Core Processor"),
+            (
+                "AMD Ryzen™ 9 7950X3D",
+                "AMD Ryzen 9 7950X3D 16-Core Processor",
+            ),
+            (
+                "AMD Phenom™ II 1075T",
+                "AMD Phenom(tm) II X6 1075T Processor",
+            ),
+            (
+                "A10-7850K with Radeon™ R7 Series",
+                "AMD A10-7850K Radeon R7, 12 Compute Cores 4C+8G",
+            ),
         ];
 
         for pairing in pairings {
             let found_cpu = cache.find(pairing.1).unwrap();
             assert_eq!(found_cpu.name, pairing.0, "With an input of {:?}, a database result of {:?} was expected, while {:?} was returned instead.", pairing.1, pairing.0, found_cpu.name);
         }
+        let epyc = cache.find("AMD EPYC 7763 64-Core Processor").unwrap();
+        assert_eq!(epyc.name, "AMD EPYC™ 7763");
+        assert_eq!(epyc.spec.segments, [Segment::Server]);
+        assert_eq!(epyc.spec.cores, Some(64));
+        let arrow_lake = cache.find("Intel(R) Core(TM) Ultra 9 285K").unwrap();
+        assert_eq!(arrow_lake.name, "Intel® Core™ Ultra 9 Processor 285K");
+        assert_eq!(arrow_lake.spec.performance_cores, Some(8));
+        assert_eq!(arrow_lake.spec.efficient_cores, Some(16));
+    }
+
+    #[test]
+    fn ranked_candidates() {
+        let cache = CpuCache::new();
+        let candidates = cache
+            .find_candidates("Intel(R) Core(TM) i5-9400 CPU @ 2.90GHz", 5)
+            .unwrap();
+        assert_eq!(candidates.len(), 5);
+        assert_eq!(candidates[0].cpu.name, "Intel® Core™ i5-9400 Processor");
+        assert!(candidates[0].confidence >= STRICT_CONFIDENCE_THRESHOLD);
+        assert!(candidates
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+        // every other candidate has a different prefix or suffix
+        assert!(candidates[1..]
+            .iter()
+            .all(|c| c.confidence < STRICT_CONFIDENCE_THRESHOLD));
+        assert_eq!(
+            cache
+                .find_candidates("Intel Core i5-9400", 1)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(cache.find_candidates("Intel Core QQ-1", 5).is_err());
+    }
+
+    #[test]
+    fn find_by_part() {
+        let cache = CpuCache::new();
+        let cases = [
+            ("100-000001683", "AMD Ryzen™ AI 9 HX PRO 475"),
+            ("100-100000065BOX", "AMD Ryzen™ 5 5600X"),
+            // listed as `FP7:100-000000538 FP7r2:100-000000550`
+            ("100-000000550", "AMD Ryzen™ 7 PRO 6850U"),
+            // listed as `YD200GC6M2OFB / YD20GGC6M2OFB`
+            ("YD20GGC6M2OFB", "AMD Athlon™ 200GE"),
+            ("yd3000c6m2ofh", "AMD Athlon™ 3000G"),
+            ("i5-9400f", "Intel® Core™ i5-9400F Processor"),
+            (" E5-2680 v4 ", "Intel® Xeon® Processor E5-2680 v4"),
+        ];
+        for (part_number, name) in cases {
+            assert_eq!(
+                cache.find_by_part(part_number).unwrap().name,
+                name,
+                "part number: {part_number:?}"
+            );
+        }
+        assert_eq!(
+            cache.find_by_part("100-999999999").unwrap_err(),
+            CpuLookupError::UnknownPart(String::from("100-999999999"))
+        );
+    }
+
+    #[test]
+    fn route_by_vendor() {
+        let cache = CpuCache::new();
+        assert_eq!(
+            cache.find("amd ryzen 5 3600").unwrap().name,
+            "AMD Ryzen™ 5 3600"
+        );
+        assert_eq!(
+            cache.find("Ryzen 5 3600 6-Core Processor").unwrap().name,
+            "AMD Ryzen™ 5 3600"
+        );
+        let snapdragon = cache
+            .find("Snapdragon(R) X Elite - X1E78100 - Qualcomm(R) Oryon(TM) CPU")
+            .unwrap();
+        assert_eq!(snapdragon.name, "Snapdragon® X Elite X1E-78-100");
+        assert_eq!(snapdragon.spec.cores, Some(12));
+        assert_eq!(
+            cache
+                .find("Snapdragon(R) X Plus - X1P42100 - Qualcomm(R) Oryon(TM) CPU")
+                .unwrap()
+                .name,
+            "Snapdragon® X Plus X1P-42-100"
+        );
+        assert_eq!(
+            cache
+                .find("Snapdragon(R) X - X126100 - Qualcomm(R) Oryon(TM) CPU")
+                .unwrap()
+                .name,
+            "Snapdragon® X X1-26-100"
+        );
+        assert_eq!(
+            cache.find("Hygon C86 3250 8-core Processor").unwrap_err(),
+            CpuLookupError::UnsupportedVendor(CpuVendor::Hygon)
+        );
+        assert_eq!(
+            cache.find("Apple M2").unwrap_err(),
+            CpuLookupError::UnknownVendor(String::from("Apple M2"))
+        );
+    }
+
+    #[test]
+    fn attribute_sections() {
+        let cache = CpuCache::new();
+        let cpu = cache
+            .find("Intel(R) Core(TM) i5-9400F CPU @ 2.90GHz")
+            .unwrap();
+        let names: Vec<&str> = cpu.sections.iter().map(|s| s.name).collect();
+        assert_eq!(
+            &names[..3],
+            [
+                "Essentials",
+                "CPU Specifications",
+                "Supplemental Information"
+            ]
+        );
+        assert_eq!(
+            cpu.sections[0].attributes[0],
+            (
+                "Product Collection",
+                "9th Generation Intel® Core™ i5 Processors"
+            )
+        );
+        // labels don't keep their footnote markers, and the last attribute in a section isn't dropped
+        assert_eq!(cpu.attributes.get("Intel® 64"), Some(&"Yes"));
+        assert!(cpu.attributes.contains_key("Recommended Customer Price"));
+        assert_eq!(
+            cpu.attributes.len(),
+            cpu.sections
+                .iter()
+                .map(|s| s.attributes.len())
+                .sum::<usize>()
+        );
+        let cpu = cache.find("AMD Ryzen 5 5600X 6-Core Processor").unwrap();
+        assert_eq!(cpu.sections[0].name, "Specifications");
     }
 }
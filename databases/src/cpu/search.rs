@@ -0,0 +1,244 @@
+//! Browsing the cpu databases by their normalized specs, rather than looking up a single cpu by name
+use super::{Cpu, CpuCache, CpuSpec, CpuVendor, Segment, AMD_CPUS, INTEL_CPUS, QUALCOMM_CPUS};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use tracing::debug;
+
+/// Filters for [CpuCache::search], a cpu has to match every filter that's set
+#[derive(Clone, Debug, Default)]
+pub struct CpuFilter {
+    pub vendor: Option<CpuVendor>,
+    /// Matches any cpu whose family contains this, ignoring case, so "xeon" matches "Intel Xeon 6 processors"
+    pub family: Option<String>,
+    /// Matches any cpu that lists this socket, ignoring case. Intel's "FC" (flip chip) prefix is optional, so
+    /// "LGA1700" matches "FCLGA1700"
+    pub socket: Option<String>,
+    pub min_cores: Option<u32>,
+    pub max_cores: Option<u32>,
+    pub launch_year: Option<u16>,
+    pub segment: Option<Segment>,
+}
+
+/// What search results are sorted by
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CpuSortKey {
+    #[default]
+    Name,
+    Cores,
+    Threads,
+    BaseClock,
+    BoostClock,
+    Tdp,
+    LaunchDate,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// A cpu in the catalog, the specs of every cpu are read once so that searching is cheap
+#[derive(Clone, Debug, Serialize)]
+pub struct CatalogEntry {
+    pub vendor: CpuVendor,
+    pub name: &'static str,
+    pub spec: CpuSpec,
+}
+
+/// A page of search results
+#[derive(Debug, Serialize)]
+pub struct SearchResults<'a> {
+    /// How many cpus matched the filter, across every page
+    pub total: usize,
+    pub cpus: Vec<&'a CatalogEntry>,
+}
+
+impl CpuCache {
+    /// Find every cpu that matches `filter`, sorted by `sort`, and return up to `limit` of them, starting at `offset`.
+    /// Cpus that don't list the value being sorted by are always placed last, and ties are broken by name
+    #[tracing::instrument(name = "cpu_search", skip(self))]
+    pub fn search(
+        &self,
+        filter: &CpuFilter,
+        sort: CpuSortKey,
+        order: SortOrder,
+        offset: usize,
+        limit: usize,
+    ) -> SearchResults<'_> {
+        // the catalog is already sorted by name
+        let mut matches: Vec<&CatalogEntry> = self
+            .catalog
+            .get_or_init(build_catalog)
+            .iter()
+            .filter(|entry| filter.matches(entry))
+            .collect();
+        if sort == CpuSortKey::Name {
+            if order == SortOrder::Desc {
+                matches.reverse();
+            }
+        } else {
+            matches.sort_by(
+                |a, b| match (sort_value(&a.spec, sort), sort_value(&b.spec, sort)) {
+                    (Some(a), Some(b)) if order == SortOrder::Desc => b.total_cmp(&a),
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                },
+            );
+        }
+        SearchResults {
+            total: matches.len(),
+            cpus: matches.into_iter().skip(offset).take(limit).collect(),
+        }
+    }
+}
+
+/// Read the spec of every cpu in every database, sorted by name
+fn build_catalog() -> Vec<CatalogEntry> {
+    let mut catalog: Vec<CatalogEntry> = [
+        (CpuVendor::Intel, &INTEL_CPUS),
+        (CpuVendor::Amd, &AMD_CPUS),
+        (CpuVendor::Qualcomm, &QUALCOMM_CPUS),
+    ]
+    .into_iter()
+    .flat_map(|(vendor, database)| {
+        database
+            .entries()
+            .map(move |(name, attributes)| CatalogEntry {
+                vendor,
+                name,
+                spec: Cpu::from((*name, attributes)).spec,
+            })
+    })
+    .collect();
+    catalog.sort_by_key(|entry| entry.name);
+    debug!("Catalog generated for every CPU");
+    catalog
+}
+
+impl CpuFilter {
+    fn matches(&self, entry: &CatalogEntry) -> bool {
+        let spec = &entry.spec;
+        if self.vendor.is_some_and(|vendor| vendor != entry.vendor) {
+            return false;
+        }
+        if let Some(family) = &self.family {
+            let family = family.to_lowercase();
+            if !spec
+                .family
+                .as_ref()
+                .is_some_and(|f| f.to_lowercase().contains(&family))
+            {
+                return false;
+            }
+        }
+        if let Some(socket) = &self.socket {
+            let socket = socket.trim();
+            // intel lists several sockets like "FCLGA1366 | LGA1366", AMD uses commas
+            let listed = spec.socket.as_deref().unwrap_or_default().split([',', '|']);
+            if !listed.map(str::trim).any(|s| {
+                s.eq_ignore_ascii_case(socket)
+                    || s.strip_prefix("FC")
+                        .is_some_and(|s| s.eq_ignore_ascii_case(socket))
+            }) {
+                return false;
+            }
+        }
+        if self.min_cores.is_some() || self.max_cores.is_some() {
+            let Some(cores) = spec.cores else {
+                return false;
+            };
+            if self.min_cores.is_some_and(|min| cores < min)
+                || self.max_cores.is_some_and(|max| cores > max)
+            {
+                return false;
+            }
+        }
+        if let Some(year) = self.launch_year {
+            if spec.launch_date.map(|d| d.year) != Some(year) {
+                return false;
+            }
+        }
+        if let Some(segment) = self.segment {
+            if !spec.segments.contains(&segment) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The value of a spec that's sorted by, as a float so that every key can be compared the same way
+fn sort_value(spec: &CpuSpec, sort: CpuSortKey) -> Option<f64> {
+    match sort {
+        CpuSortKey::Name => None,
+        CpuSortKey::Cores => spec.cores.map(f64::from),
+        CpuSortKey::Threads => spec.threads.map(f64::from),
+        CpuSortKey::BaseClock => spec.base_clock_mhz.map(f64::from),
+        CpuSortKey::BoostClock => spec.boost_clock_mhz.map(f64::from),
+        CpuSortKey::Tdp => spec.tdp_watts.map(f64::from),
+        // intel only lists the quarter, so those cpus are sorted as if they launched at the start of it
+        CpuSortKey::LaunchDate => spec.launch_date.map(|d| {
+            let month = d.month.unwrap_or((d.quarter - 1) * 3 + 1);
+            f64::from(d.year) * 10_000.0 + f64::from(month) * 100.0 + f64::from(d.day.unwrap_or(0))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CatalogEntry, CpuFilter, CpuSortKey, SortOrder};
+    use crate::cpu::{CpuCache, CpuVendor, Segment};
+
+    #[test]
+    fn filter_catalog() {
+        let cache = CpuCache::new();
+        let filter = CpuFilter {
+            socket: Some(String::from("am4")),
+            min_cores: Some(8),
+            ..Default::default()
+        };
+        let results = cache.search(&filter, CpuSortKey::Cores, SortOrder::Desc, 0, 500);
+        assert!(results.total > 0);
+        assert_eq!(results.total, results.cpus.len());
+        for entry in &results.cpus {
+            assert_eq!(entry.vendor, CpuVendor::Amd);
+            assert_eq!(entry.spec.socket.as_deref(), Some("AM4"));
+            assert!(entry.spec.cores.unwrap() >= 8);
+        }
+        assert!(results
+            .cpus
+            .windows(2)
+            .all(|pair| pair[0].spec.cores >= pair[1].spec.cores));
+
+        // the "FC" prefix is optional
+        let filter = CpuFilter {
+            vendor: Some(CpuVendor::Intel),
+            socket: Some(String::from("LGA1700")),
+            segment: Some(Segment::Desktop),
+            launch_year: Some(2022),
+            ..Default::default()
+        };
+        let page = cache.search(&filter, CpuSortKey::Name, SortOrder::Asc, 2, 3);
+        assert!(page.total > 5);
+        assert_eq!(page.cpus.len(), 3);
+        let everything = cache.search(&filter, CpuSortKey::Name, SortOrder::Asc, 0, usize::MAX);
+        let names = |cpus: &[&CatalogEntry]| cpus.iter().map(|c| c.name).collect::<Vec<&str>>();
+        assert_eq!(names(&everything.cpus[2..5]), names(&page.cpus));
+        assert!(everything.cpus.windows(2).all(|p| p[0].name <= p[1].name));
+
+        let filter = CpuFilter {
+            family: Some(String::from("threadripper")),
+            max_cores: Some(16),
+            ..Default::default()
+        };
+        let results = cache.search(&filter, CpuSortKey::Name, SortOrder::Asc, 0, 500);
+        assert!(results.total > 0);
+        assert!(results.cpus.iter().all(|c| c.spec.cores.unwrap() <= 16));
+    }
+}
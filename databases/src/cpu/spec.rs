@@ -0,0 +1,364 @@
+//! The Intel and AMD databases use different names for the same attributes, and store values as text with
+//! inconsistent units ("Up to 5.2 GHz", "12 MB", "28W", "-40°C to 110°C"). This module reads them into a common shape.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The specifications of a cpu, normalized from the raw attributes of either database.
+/// Anything that isn't listed for a cpu, or couldn't be read, is left as `None`
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CpuSpec {
+    /// The product line or collection the cpu belongs to, without trademark symbols, like "Ryzen PRO" or
+    /// "9th Generation Intel Core i5 Processors"
+    pub family: Option<String>,
+    /// The kinds of devices the cpu is intended for. AMD cpus can be listed under several segments
+    pub segments: Vec<Segment>,
+    /// The total number of cores, including every kind of core on hybrid cpus
+    pub cores: Option<u32>,
+    pub threads: Option<u32>,
+    /// The number of performance cores, only listed for hybrid Intel cpus
+    pub performance_cores: Option<u32>,
+    /// The number of efficient cores (including low power efficient cores), only listed for hybrid Intel cpus
+    pub efficient_cores: Option<u32>,
+    /// The base clock of the cpu in MHz, the performance core base clock on hybrid cpus
+    pub base_clock_mhz: Option<u32>,
+    /// The highest boost clock of the cpu in MHz
+    pub boost_clock_mhz: Option<u32>,
+    pub l1_cache_kib: Option<u32>,
+    pub l2_cache_kib: Option<u32>,
+    /// The last level cache, Intel's "Smart Cache" is counted as L3
+    pub l3_cache_kib: Option<u32>,
+    /// The TDP, or the processor base power on newer Intel cpus, in watts
+    pub tdp_watts: Option<f32>,
+    /// The maximum junction temperature in °C
+    pub tjmax_celsius: Option<f32>,
+    /// The socket or package, like "AM4" or "FCLGA1700"
+    pub socket: Option<String>,
+    /// The process the cpu was manufactured on, like "14nm" or "TSMC 7nm FinFET"
+    pub lithography: Option<String>,
+    pub launch_date: Option<LaunchDate>,
+    /// Every generation of memory the cpu supports, like `["DDR4", "LPDDR4X"]`
+    pub memory_types: Vec<String>,
+}
+
+/// A kind of device a cpu is intended for, read from intel's "Vertical Segment" and AMD's "Form Factor"
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Segment {
+    Desktop,
+    Mobile,
+    Server,
+    Workstation,
+    Embedded,
+    Handheld,
+}
+
+/// When a cpu was launched. Intel only lists the quarter, while AMD usually lists the full date
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct LaunchDate {
+    pub year: u16,
+    /// From 1 to 4
+    pub quarter: u8,
+    /// From 1 to 12
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+
+impl CpuSpec {
+    /// Read the spec from the raw attributes of a cpu from either database
+    pub fn from_attributes(attributes: &HashMap<&str, &str>) -> Self {
+        let attributes: HashMap<&str, &str> = attributes
+            .iter()
+            .map(|(&k, v)| (k, v.trim()))
+            .filter(|(_, v)| !v.is_empty())
+            .collect();
+        // returns the value of the first attribute that exists out of the provided names
+        let first = |names: &[&str]| names.iter().find_map(|n| attributes.get(n).copied());
+
+        let efficient_cores: Vec<u32> = [
+            "# of Efficient-cores",
+            "# of Efficiency-cores",
+            "# of Low Power Efficient-cores",
+        ]
+        .iter()
+        .filter_map(|n| attributes.get(n).and_then(|v| parse_count(v)))
+        .collect();
+        let (l2_cache_kib, l3_cache_kib) = match first(&["Cache"]) {
+            // intel lists a single cache, the label in the value says which level it is
+            Some(cache) if cache.contains("L2") => (parse_kib(cache), None),
+            Some(cache) => (
+                first(&["Total L2 Cache"]).and_then(parse_kib),
+                parse_kib(cache),
+            ),
+            None => (
+                first(&["L2 Cache", "Total L2 Cache"]).and_then(parse_kib),
+                first(&["L3 Cache"]).and_then(parse_kib),
+            ),
+        };
+
+        Self {
+            family: first(&["Family", "Product Collection"]).map(parse_family),
+            segments: first(&["Vertical Segment", "Form Factor"])
+                .map(parse_segments)
+                .unwrap_or_default(),
+            cores: first(&["Total Cores", "# of Cores", "# of CPU Cores"]).and_then(parse_count),
+            threads: first(&["Total Threads", "# of Threads"]).and_then(parse_count),
+            performance_cores: first(&["# of Performance-cores"]).and_then(parse_count),
+            efficient_cores: (!efficient_cores.is_empty()).then(|| efficient_cores.iter().sum()),
+            base_clock_mhz: first(&[
+                "Processor Base Frequency",
+                "Performance-core Base Frequency",
+                "P-core Base Frequency",
+                "Base Clock",
+            ])
+            .and_then(parse_mhz),
+            boost_clock_mhz: first(&[
+                "Max Turbo Frequency",
+                "Performance-core Max Turbo Frequency",
+                "Max. Boost Clock",
+                "Burst Frequency",
+            ])
+            .and_then(parse_mhz),
+            l1_cache_kib: first(&["L1 Cache"]).and_then(parse_kib),
+            l2_cache_kib,
+            l3_cache_kib,
+            tdp_watts: first(&["TDP", "Processor Base Power", "Default TDP"])
+                .and_then(|v| parse_unit(v, "W")),
+            tjmax_celsius: first(&["TJUNCTION", "Max. Operating Temperature (Tjmax)"])
+                .and_then(parse_celsius),
+            socket: first(&["Sockets Supported", "CPU Socket"]).map(String::from),
+            lithography: first(&[
+                "Lithography",
+                "CPU Lithography",
+                "Processor Technology for CPU Cores",
+            ])
+            .map(|v| v.replace(" nm", "nm")),
+            launch_date: first(&["Launch Date"]).and_then(parse_launch_date),
+            memory_types: first(&["Memory Types", "System Memory Type"])
+                .map(parse_memory_types)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn parse_count(value: &str) -> Option<u32> {
+    value.parse().ok()
+}
+
+/// Read the first number in a value, and the unit directly after it, so "Up to 4.2 GHz" is read as `(4.2, "GHz")`
+fn parse_quantity(value: &str) -> Option<(f32, &str)> {
+    let start = value.find(|c: char| c.is_ascii_digit())?;
+    let value = &value[start..];
+    let end = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let number = value[..end].parse().ok()?;
+    let unit = value[end..].trim_start();
+    let unit_end = unit
+        .find(|c: char| !c.is_alphabetic() && c != '°')
+        .unwrap_or(unit.len());
+    Some((number, &unit[..unit_end]))
+}
+
+/// Read a value in `unit`, `None` is returned if the value is in any other unit
+fn parse_unit(value: &str, unit: &str) -> Option<f32> {
+    parse_quantity(value)
+        .filter(|(_, u)| *u == unit)
+        .map(|(n, _)| n)
+}
+
+fn parse_mhz(value: &str) -> Option<u32> {
+    match parse_quantity(value)? {
+        (n, "GHz") => Some((n * 1000.0).round() as u32),
+        (n, "MHz") => Some(n.round() as u32),
+        _ => None,
+    }
+}
+
+fn parse_kib(value: &str) -> Option<u32> {
+    match parse_quantity(value)? {
+        (n, "KB") => Some(n.round() as u32),
+        (n, "MB") => Some((n * 1024.0).round() as u32),
+        (n, "GB") => Some((n * 1024.0 * 1024.0).round() as u32),
+        _ => None,
+    }
+}
+
+/// Temperatures are sometimes listed as a range, like "-40°C to 110°C", in which case the maximum is used
+fn parse_celsius(value: &str) -> Option<f32> {
+    let maximum = value.rsplit(" to ").next()?;
+    parse_unit(maximum, "°C")
+}
+
+/// Remove trademark symbols and the whitespace they leave behind, so "Intel® Core™ i5" is read as "Intel Core i5"
+fn parse_family(value: &str) -> String {
+    value
+        .replace(['®', '™'], " ")
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Read a comma separated list of segments, like "Laptops, Desktops". Entries that aren't segments, like
+/// "Boxed Processor", are skipped
+fn parse_segments(value: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for entry in value.split(',').map(str::trim) {
+        let segment = match entry {
+            "Desktop" | "Desktops" | "1L Desktops" => Segment::Desktop,
+            "Mobile" | "Laptops" => Segment::Mobile,
+            "Server" | "Servers" => Segment::Server,
+            "Workstation" | "Mobile Workstations" => Segment::Workstation,
+            "Embedded" => Segment::Embedded,
+            "Handheld" => Segment::Handheld,
+            _ => continue,
+        };
+        if !segments.contains(&segment) {
+            segments.push(segment);
+        }
+    }
+    segments
+}
+
+/// Read a launch date in one of the forms used by the databases: "Q1'19", "Q3'2011", "Q4 2024", or "7/21/2020"
+fn parse_launch_date(value: &str) -> Option<LaunchDate> {
+    if let Some(quarter_and_year) = value.strip_prefix('Q') {
+        let (quarter, year) = quarter_and_year.split_once(['\'', ' '])?;
+        let quarter: u8 = quarter.parse().ok()?;
+        let mut year: u16 = year.trim().parse().ok()?;
+        // intel abbreviates years to two digits
+        if year < 100 {
+            year += if year < 70 { 2000 } else { 1900 };
+        }
+        return (1..=4).contains(&quarter).then_some(LaunchDate {
+            year,
+            quarter,
+            month: None,
+            day: None,
+        });
+    }
+    let mut fields = value.split('/').map(|f| f.trim().parse::<u16>().ok());
+    let (Some(Some(month)), Some(Some(day)), Some(Some(year)), None) =
+        (fields.next(), fields.next(), fields.next(), fields.next())
+    else {
+        return None;
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(LaunchDate {
+        year,
+        quarter: (month as u8 - 1) / 3 + 1,
+        month: Some(month as u8),
+        day: Some(day as u8),
+    })
+}
+
+/// Find every memory generation mentioned in a value, like "DDR4" in "DDR4-2666", or "LPDDR5X" in "LPDDR5X (FP8)"
+fn parse_memory_types(value: &str) -> Vec<String> {
+    let mut memory_types: Vec<String> = Vec::new();
+    for (index, _) in value.match_indices("DDR") {
+        let start = if value[..index].ends_with("LP") {
+            index - 2
+        } else {
+            index
+        };
+        let rest = &value[index + "DDR".len()..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && !c.is_ascii_uppercase())
+            .unwrap_or(rest.len());
+        let generation = value[start..index + "DDR".len() + end].to_string();
+        if !memory_types.contains(&generation) {
+            memory_types.push(generation);
+        }
+    }
+    memory_types
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_launch_date, parse_memory_types, parse_segments, LaunchDate, Segment};
+    use crate::cpu::CpuCache;
+
+    #[test]
+    fn normalize_intel_spec() {
+        let cache = CpuCache::new();
+        let cpu = cache
+            .find("Intel(R) Core(TM) i5-9400F CPU @ 2.90GHz")
+            .unwrap();
+        let spec = cpu.spec;
+        assert_eq!(spec.cores, Some(6));
+        assert_eq!(spec.threads, Some(6));
+        assert_eq!(spec.base_clock_mhz, Some(2900));
+        assert_eq!(spec.boost_clock_mhz, Some(4100));
+        assert_eq!(spec.l3_cache_kib, Some(9 * 1024));
+        assert_eq!(spec.tdp_watts, Some(65.0));
+        assert_eq!(spec.tjmax_celsius, Some(100.0));
+        assert_eq!(spec.lithography.as_deref(), Some("14nm"));
+        assert_eq!(
+            spec.family.as_deref(),
+            Some("9th Generation Intel Core i5 Processors")
+        );
+        assert_eq!(spec.segments, [Segment::Desktop]);
+        assert_eq!(spec.memory_types, ["DDR4"]);
+        assert_eq!(
+            spec.launch_date,
+            Some(LaunchDate {
+                year: 2019,
+                quarter: 1,
+                month: None,
+                day: None
+            })
+        );
+    }
+
+    #[test]
+    fn normalize_amd_spec() {
+        let cache = CpuCache::new();
+        let cpu = cache.find("AMD Ryzen 5 3600 6-Core Processor").unwrap();
+        let spec = cpu.spec;
+        assert_eq!(spec.cores, Some(6));
+        assert_eq!(spec.threads, Some(12));
+        assert_eq!(spec.base_clock_mhz, Some(3600));
+        assert_eq!(spec.boost_clock_mhz, Some(4200));
+        assert_eq!(spec.l2_cache_kib, Some(3 * 1024));
+        assert_eq!(spec.l3_cache_kib, Some(32 * 1024));
+        assert_eq!(spec.tdp_watts, Some(65.0));
+        assert_eq!(spec.socket.as_deref(), Some("AM4"));
+        assert_eq!(spec.family.as_deref(), Some("Ryzen"));
+        assert_eq!(spec.segments, [Segment::Desktop]);
+        assert_eq!(spec.memory_types, ["DDR4"]);
+    }
+
+    #[test]
+    fn basic_parse_values() {
+        assert_eq!(
+            parse_launch_date("1/5/2026"),
+            Some(LaunchDate {
+                year: 2026,
+                quarter: 1,
+                month: Some(1),
+                day: Some(5)
+            })
+        );
+        assert_eq!(parse_launch_date("Q4 2024").map(|d| d.year), Some(2024));
+        assert_eq!(parse_launch_date("Q3'06").map(|d| d.year), Some(2006));
+        assert_eq!(parse_launch_date("Q5'06"), None);
+        assert_eq!(
+            parse_memory_types("DDR4 - Up to 3200 , LPDDR4 - Up to 4266"),
+            ["DDR4", "LPDDR4"]
+        );
+        assert_eq!(
+            parse_memory_types("DDR5 (FP8) , LPDDR5X (FP8)"),
+            ["DDR5", "LPDDR5X"]
+        );
+        assert!(parse_memory_types("Not Listed").is_empty());
+        assert_eq!(
+            parse_segments("Desktops, 1L Desktops, Laptops, Mobile Workstations"),
+            [Segment::Desktop, Segment::Mobile, Segment::Workstation]
+        );
+        assert_eq!(
+            parse_segments("Boxed Processor, Desktops"),
+            [Segment::Desktop]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Suggesting cpu names as they're typed. Unlike [CpuCache::find], which is tuned for the full names reported by WMI,
+//! this matches partial input against the names in the databases
+use super::{remove_trademarks, CpuCache, CpuDatabase};
+use std::collections::{BTreeMap, HashSet};
+
+/// Every word in every cpu name, mapped to the names that contain it. Words are kept sorted, so every word that starts
+/// with a prefix can be found with a range lookup
+pub(super) struct SuggestIndex {
+    names: Vec<&'static str>,
+    words: BTreeMap<String, Vec<usize>>,
+}
+
+impl SuggestIndex {
+    /// Index the name of every cpu in every database
+    pub(super) fn new(databases: &[&'static CpuDatabase]) -> Self {
+        let mut names: Vec<&'static str> = databases
+            .iter()
+            .flat_map(|database| database.keys().copied())
+            .collect();
+        names.sort_unstable();
+        let mut words: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (id, name) in names.iter().enumerate() {
+            for word in split_words(name) {
+                let ids = words.entry(word).or_default();
+                // a word can show up more than once in the same name
+                if ids.last() != Some(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        Self { names, words }
+    }
+
+    /// Find the ids of every name with a word that starts with `prefix`
+    fn names_with_prefix(&self, prefix: &str) -> HashSet<usize> {
+        self.words
+            .range(prefix.to_string()..)
+            .take_while(|(word, _)| word.starts_with(prefix))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect()
+    }
+}
+
+impl CpuCache {
+    /// Suggest up to `limit` cpu names for partially typed input, like "ryzen 5 36". Every word in the input has to
+    /// start a word in the name, but the order of words, case, and trademark symbols are ignored. Names where more of
+    /// the input matches whole words are ranked first, followed by shorter names
+    #[tracing::instrument(name = "cpu_suggest", skip(self))]
+    pub fn suggest(&self, input: &str, limit: usize) -> Vec<&'static str> {
+        let index = &self.suggest_index;
+        let input_words = split_words(input);
+        let mut matches: Option<HashSet<usize>> = None;
+        for word in &input_words {
+            let ids = index.names_with_prefix(word);
+            matches = Some(match matches {
+                Some(matches) => matches.intersection(&ids).copied().collect(),
+                None => ids,
+            });
+        }
+        let mut matches: Vec<(usize, usize)> = matches
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| {
+                let name_words: HashSet<String> =
+                    split_words(index.names[id]).into_iter().collect();
+                let whole_words = input_words
+                    .iter()
+                    .filter(|w| name_words.contains(*w))
+                    .count();
+                (id, whole_words)
+            })
+            .collect();
+        matches.sort_by_key(|(id, whole_words)| {
+            let name = index.names[*id];
+            (std::cmp::Reverse(*whole_words), name.len(), name)
+        });
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(id, _)| index.names[id])
+            .collect()
+    }
+}
+
+/// Split a name into lowercase words, without trademark symbols or punctuation. Words with dashes are also split
+/// apart, so "i5-9400F" can be found by typing "i5-94", "i5" or "9400"
+fn split_words(name: &str) -> Vec<String> {
+    let name = remove_trademarks(name).to_lowercase();
+    name.split(|c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')' | '/'))
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            let parts = word.split('-').filter(|part| !part.is_empty());
+            std::iter::once(word).chain(parts.filter(move |part| *part != word))
+        })
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cpu::CpuCache;
+
+    #[test]
+    fn suggest_names() {
+        let cache = CpuCache::new();
+        assert_eq!(
+            cache.suggest("ryzen 5 36", 3),
+            [
+                "AMD Ryzen™ 5 3600",
+                "AMD Ryzen™ 5 3600X",
+                "AMD Ryzen™ 5 3600XT"
+            ]
+        );
+        // order, case and trademark symbols don't matter
+        assert_eq!(
+            cache.suggest("9400F CORE(TM) i5", 1),
+            ["Intel® Core™ i5-9400F Processor"]
+        );
+        assert_eq!(
+            cache.suggest("i5-940", 1),
+            ["Intel® Core™ i5-9400 Processor"]
+        );
+        assert_eq!(
+            cache.suggest("x elite 84", 1),
+            ["Snapdragon® X Elite X1E-84-100"]
+        );
+        assert!(cache
+            .suggest("xeon", 10)
+            .iter()
+            .all(|name| name.contains("Xeon")));
+        assert_eq!(cache.suggest("xeon", 10).len(), 10);
+        assert!(cache.suggest("ryzen 5 qq", 10).is_empty());
+        assert!(cache.suggest("", 10).is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+//! Working out who made a cpu from its name, so that it can be looked up in the right database.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The cpu vendors that can be recognized. Not every vendor has a database to look cpus up in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuVendor {
+    Intel,
+    Amd,
+    Hygon,
+    Via,
+    Zhaoxin,
+    Qualcomm,
+}
+
+impl CpuVendor {
+    /// Try to work out the vendor of a cpu from its name, a CPUID vendor string (like "AuthenticAMD"), or a string
+    /// containing either, like "AuthenticAMD Family 25 Model 33". Matching isn't case sensitive.
+    ///
+    /// The input is split into words, and the vendor of the first word that is a brand, a CPUID vendor string, or the
+    /// name of a product line (like "Ryzen" or "Xeon") is returned. `None` is returned if no word is recognized,
+    /// rather than guessing
+    pub fn detect(input: &str) -> Option<Self> {
+        let words: Vec<String> = input
+            .split(|c: char| !c.is_alphanumeric())
+            .map(str::to_lowercase)
+            .collect();
+        // "core" is also used to count cores by other vendors, like "Apple M2 8-core", so it's only read as intel's
+        // product line when there's an intel series next to it, like "Core Ultra 7 155H"
+        let intel_core = words.iter().any(|w| is_intel_series(w));
+        words.iter().find_map(|word| match word.as_str() {
+            "core" if intel_core => Some(Self::Intel),
+            _ => Self::from_word(word),
+        })
+    }
+
+    /// Identify the vendor that a single lowercase word belongs to
+    fn from_word(word: &str) -> Option<Self> {
+        match word {
+            // brands and CPUID vendor strings
+            "intel" | "genuineintel" => Some(Self::Intel),
+            "amd" | "authenticamd" => Some(Self::Amd),
+            "hygon" | "hygongenuine" => Some(Self::Hygon),
+            "via" | "centaurhauls" => Some(Self::Via),
+            // zhaoxin's CPUID vendor string is "  Shanghai  "
+            "zhaoxin" | "shanghai" => Some(Self::Zhaoxin),
+            "qualcomm" => Some(Self::Qualcomm),
+            // product lines
+            "xeon" | "pentium" | "celeron" | "atom" | "itanium" => Some(Self::Intel),
+            "ryzen" | "threadripper" | "epyc" | "athlon" | "phenom" | "opteron" | "sempron"
+            | "turion" => Some(Self::Amd),
+            "snapdragon" => Some(Self::Qualcomm),
+            // intel core model numbers are sometimes reported without a brand, like "i5-9400F"
+            "i3" | "i5" | "i7" | "i9" => Some(Self::Intel),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a lowercase word is a series of intel core cpus, like "i7", "ultra" or "n100"
+fn is_intel_series(word: &str) -> bool {
+    match word {
+        "intel" | "i3" | "i5" | "i7" | "i9" | "ultra" => true,
+        _ => word
+            .strip_prefix('n')
+            .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())),
+    }
+}
+
+impl fmt::Display for CpuVendor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Intel => "Intel",
+            Self::Amd => "AMD",
+            Self::Hygon => "Hygon",
+            Self::Via => "VIA",
+            Self::Zhaoxin => "Zhaoxin",
+            Self::Qualcomm => "Qualcomm",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CpuVendor;
+
+    #[test]
+    fn basic_detect() {
+        let cases = [
+            ("AMD Ryzen 5 3600 6-Core Processor", Some(CpuVendor::Amd)),
+            ("amd ryzen 5 3600", Some(CpuVendor::Amd)),
+            ("AuthenticAMD Family 25 Model 33", Some(CpuVendor::Amd)),
+            ("Ryzen 7 5800X3D", Some(CpuVendor::Amd)),
+            (
+                "Intel(R) Core(TM) i5-9400F CPU @ 2.90GHz",
+                Some(CpuVendor::Intel),
+            ),
+            ("GenuineIntel Family 6 Model 158", Some(CpuVendor::Intel)),
+            ("Xeon E5-2680 v4", Some(CpuVendor::Intel)),
+            ("i7-14700K", Some(CpuVendor::Intel)),
+            ("Hygon C86 3250 8-core Processor", Some(CpuVendor::Hygon)),
+            ("VIA Nano U3500@1000MHz", Some(CpuVendor::Via)),
+            ("ZHAOXIN KaiXian KX-U6780A@2.7GHz", Some(CpuVendor::Zhaoxin)),
+            (
+                "Snapdragon(R) X Elite - X1E78100 - Qualcomm(R) Oryon(TM) CPU",
+                Some(CpuVendor::Qualcomm),
+            ),
+            // the intel cpu with amd graphics is still an intel cpu
+            (
+                "Intel(R) Core(TM) i7-8809G CPU with Radeon(TM) RX Vega M GH Graphics",
+                Some(CpuVendor::Intel),
+            ),
+            ("Core Ultra 7 155H", Some(CpuVendor::Intel)),
+            ("Core N100", Some(CpuVendor::Intel)),
+            ("Apple M2", None),
+            // a core count isn't an intel product line
+            ("Apple M2 8-core", None),
+            ("ARM Cortex 4-core", None),
+            ("8-core processor", None),
+            ("3600", None),
+        ];
+        for (input, vendor) in cases {
+            assert_eq!(CpuVendor::detect(input), vendor, "input: {input:?}");
+        }
+    }
+}
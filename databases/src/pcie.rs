@@ -1 +0,0 @@
-pub use parsing::pcie::PcieCache;
@@ -0,0 +1,107 @@
+mod pcie_codegen;
+use parsing::identifier::IdentifierError;
+use parsing::pcie::{parse_device_identifier, ClassCode};
+pub use parsing::pcie::{DeviceClass, PcieDeviceInfo};
+use pcie_codegen::{
+    PCIE_CLASSES, PCIE_DEVICES, PCIE_PROG_IFS, PCIE_SUBCLASSES, PCIE_SUBSYSTEMS, PCIE_VENDORS,
+};
+
+/// An interface for looking up pcie devices. The database is baked into static maps by `build.rs`,
+/// so constructing the cache is free, and lookups don't allocate
+#[derive(Clone)]
+pub struct PcieCache {}
+
+impl PcieCache {
+    /// Construct a new cache
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Look up a device identifier. Everything that can be resolved is returned, so a device that isn't in the database
+    /// still has its vendor and class named. An error is only returned if the identifier is malformed, or if neither the
+    /// vendor nor the class could be found
+    #[tracing::instrument(name = "pcie_lookup", skip(self))]
+    pub fn find(&self, input: &str) -> Result<PcieDeviceInfo<'static>, IdentifierError> {
+        let (vid, did, subsys, class_code) = parse_device_identifier(input)?;
+        let vendor = PCIE_VENDORS.get(&vid).copied();
+        let device = PCIE_DEVICES
+            .get(&((vid as u32) << 16 | did as u32))
+            .copied();
+        let class = class_code.and_then(find_class);
+        if vendor.is_none() && class.is_none() {
+            return Err(IdentifierError::UnknownVendor(vid));
+        }
+
+        let mut subsystem: Option<&str> = None;
+        let mut subsystem_vendor: Option<&str> = None;
+        if let Some((ss_vid, ss_id)) = subsys {
+            subsystem = PCIE_SUBSYSTEMS
+                .get(
+                    &((vid as u64) << 48
+                        | (did as u64) << 32
+                        | (ss_vid as u64) << 16
+                        | ss_id as u64),
+                )
+                .copied();
+            subsystem_vendor = PCIE_VENDORS.get(&ss_vid).copied();
+        }
+        Ok((vendor, device, subsystem, subsystem_vendor, class))
+    }
+}
+
+impl Default for PcieCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolve a class code into the names of the class, subclass and programming interface
+fn find_class(class_code: ClassCode) -> Option<DeviceClass<'static>> {
+    let (class_id, subclass_id, prog_if_id) = class_code;
+    let class = PCIE_CLASSES.get(&class_id)?;
+    let subclass_key = (class_id as u16) << 8 | subclass_id as u16;
+    let subclass = PCIE_SUBCLASSES.get(&subclass_key).copied();
+    // a programming interface can't be named if the subclass isn't known
+    let prog_if = subclass
+        .and(prog_if_id)
+        .and_then(|id| PCIE_PROG_IFS.get(&((subclass_key as u32) << 8 | id as u32)))
+        .copied();
+    Some(DeviceClass {
+        class,
+        subclass,
+        prog_if,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PcieCache;
+    use parsing::identifier::IdentifierError;
+
+    #[test]
+    fn matches_parsed_database() {
+        let cache = PcieCache::new();
+        let parsed_cache = parsing::pcie::PcieCache::new();
+        for identifier in [
+            "PCI\\VEN_10EC&DEV_8168&SUBSYS_85051043&REV_06\\4&2A1C5B4F&0&00E5",
+            "PCI\\VEN_10EC&DEV_8168&SUBSYS_FFFF1043&REV_06",
+            "PCI\\VEN_8086&DEV_5916&CC_030000",
+            "PCI\\VEN_8086&DEV_A123&CC_0C05",
+            "pci:v000010ECd00008168sv00001043sd00008505bc02sc00i00",
+            "PCI\\VEN_8086&DEV_FFFE&CC_030000",
+            "PCI\\VEN_0002&DEV_0000&CC_030000",
+        ] {
+            assert_eq!(cache.find(identifier), parsed_cache.find(identifier));
+        }
+        assert_eq!(
+            cache
+                .find("PCI\\VEN_8086&DEV_FFFE")
+                .map(|info| (info.0, info.1)),
+            Ok((Some("Intel Corporation"), None))
+        );
+        assert_eq!(
+            cache.find("PCI\\VEN_0002&DEV_0000"),
+            Err(IdentifierError::UnknownVendor(0x0002))
+        );
+    }
+}
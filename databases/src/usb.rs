@@ -1 +0,0 @@
-pub use parsing::usb::UsbCache;
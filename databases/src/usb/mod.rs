@@ -0,0 +1,156 @@
+mod usb_codegen;
+use parsing::identifier::IdentifierError;
+use parsing::usb::{parse_device_identifier, ClassCode};
+pub use parsing::usb::{DeviceClass, UsbDeviceInfo};
+use usb_codegen::{
+    USB_AUDIO_TERMINALS, USB_BIASES, USB_CLASSES, USB_DEVICES, USB_DIALECTS, USB_HID_USAGES,
+    USB_HID_USAGE_PAGES, USB_LANGUAGES, USB_PROTOCOLS, USB_REPORT_ITEMS, USB_SUBCLASSES,
+    USB_VENDORS,
+};
+
+/// An interface for looking up usb devices and the other tables in usb.ids. The database is baked into static maps
+/// by `build.rs`, so constructing the cache is free, and lookups don't allocate
+#[derive(Clone)]
+pub struct UsbCache {}
+
+impl UsbCache {
+    /// Construct a new cache
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Look up a device identifier. Everything that can be resolved is returned, so a device that isn't in the database
+    /// still has its vendor and class named. Compatible IDs don't contain a vendor or device, so they're only resolved
+    /// to a class. An error is only returned if the identifier is malformed, or if it has a vendor id that isn't in the
+    /// database and no class to fall back on
+    #[tracing::instrument(name = "usb_lookup", skip(self))]
+    pub fn find<'a>(&self, input: &'a str) -> Result<UsbDeviceInfo<'a>, IdentifierError> {
+        let parsed_identifier = parse_device_identifier(input)?;
+        let class = parsed_identifier.class.and_then(find_class);
+        let mut vendor: Option<&str> = None;
+        let mut device: Option<&str> = None;
+        if let Some((vid, pid)) = parsed_identifier.ids {
+            vendor = USB_VENDORS.get(&vid).copied();
+            if vendor.is_none() && class.is_none() {
+                return Err(IdentifierError::UnknownVendor(vid));
+            }
+            device = pid
+                .and_then(|pid| USB_DEVICES.get(&((vid as u32) << 16 | pid as u32)))
+                .copied();
+        }
+
+        Ok(UsbDeviceInfo {
+            vendor,
+            device,
+            enumerator: parsed_identifier.enumerator,
+            revision: parsed_identifier.revision,
+            interface: parsed_identifier.interface,
+            class,
+        })
+    }
+
+    /// Search for a HID usage page, and optionally a usage within that page, returning their names.
+    /// `None` is returned if the usage page is unknown
+    #[tracing::instrument(name = "hid_usage_lookup", skip(self))]
+    pub fn find_hid_usage(
+        &self,
+        usage_page: u16,
+        usage: Option<u16>,
+    ) -> Option<(&'static str, Option<&'static str>)> {
+        let page = USB_HID_USAGE_PAGES.get(&usage_page)?;
+        let usage =
+            usage.and_then(|id| USB_HID_USAGES.get(&((usage_page as u32) << 16 | id as u32)));
+        Some((page, usage.copied()))
+    }
+
+    /// Find the name of a HID report descriptor item (like "Usage Page" or "Collection") from the item prefix byte.
+    /// The two least significant bits of the prefix encode the size of the data that follows, so they're ignored
+    pub fn find_report_item(&self, item: u8) -> Option<&'static str> {
+        USB_REPORT_ITEMS.get(&(item & 0b1111_1100)).copied()
+    }
+
+    /// Find the name of a physical descriptor bias, like "Right Hand"
+    pub fn find_bias(&self, bias: u8) -> Option<&'static str> {
+        USB_BIASES.get(&bias).copied()
+    }
+
+    /// Search for the names of the language and dialect of a USB language ID, like those used by string descriptors
+    /// (e.g. `0x0409` is English, US). The lower 10 bits of the ID are the primary language, and the upper 6 bits are
+    /// the dialect. `None` is returned if the primary language is unknown
+    #[tracing::instrument(name = "usb_language_lookup", skip(self))]
+    pub fn find_language(&self, language_id: u16) -> Option<(&'static str, Option<&'static str>)> {
+        let language = USB_LANGUAGES.get(&(language_id & 0x03ff))?;
+        Some((language, USB_DIALECTS.get(&language_id).copied()))
+    }
+
+    /// Find the name of a USB audio class terminal type, like "Headphones" for `0x0302`
+    #[tracing::instrument(name = "usb_audio_terminal_lookup", skip(self))]
+    pub fn find_audio_terminal(&self, terminal_type: u16) -> Option<&'static str> {
+        USB_AUDIO_TERMINALS.get(&terminal_type).copied()
+    }
+}
+
+impl Default for UsbCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolve a class code into the names of the class, subclass and protocol
+fn find_class(class_code: ClassCode) -> Option<DeviceClass<'static>> {
+    let (class_id, subclass_id, protocol_id) = class_code;
+    let class = USB_CLASSES.get(&class_id)?;
+    let subclass_key = subclass_id.map(|id| (class_id as u16) << 8 | id as u16);
+    let subclass = subclass_key
+        .and_then(|key| USB_SUBCLASSES.get(&key))
+        .copied();
+    // a protocol can't be named if the subclass isn't known
+    let protocol = subclass
+        .and(subclass_key.zip(protocol_id))
+        .and_then(|(key, id)| USB_PROTOCOLS.get(&((key as u32) << 8 | id as u32)))
+        .copied();
+    Some(DeviceClass {
+        class,
+        subclass,
+        protocol,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UsbCache;
+
+    #[test]
+    fn matches_parsed_database() {
+        let cache = UsbCache::new();
+        let parsed_cache = parsing::usb::UsbCache::new();
+        for identifier in [
+            "USB\\Class_03&SubClass_01&Prot_01",
+            "USB\\VID_046D&PID_C336&MI_00\\7&20BE3E95&0&0000",
+            "hid\\vid_046d&pid_c336&rev_0100&mi_00&col01",
+            "usb:v1532p0084d0200dc00dsc00dp00ic03isc01ip02in03",
+            "USB\\VID_046D&PID_FFFE",
+            "USB\\VID_046D",
+            "USB\\VID_1038&PID_1729",
+        ] {
+            assert_eq!(cache.find(identifier), parsed_cache.find(identifier));
+        }
+        assert_eq!(
+            cache.find_hid_usage(0x01, Some(0x06)),
+            Some(("Generic Desktop Controls", Some("Keyboard")))
+        );
+        assert_eq!(
+            cache
+                .find("USB\\VID_046D")
+                .map(|info| (info.vendor, info.device)),
+            Ok((Some("Logitech, Inc."), None))
+        );
+        assert_eq!(cache.find_language(0x0409), Some(("English", Some("US"))));
+        assert_eq!(
+            cache.find_report_item(0x05),
+            parsed_cache.find_report_item(0x05)
+        );
+        assert_eq!(cache.find_bias(1), parsed_cache.find_bias(1));
+        assert_eq!(cache.find_audio_terminal(0x0302), Some("Headphones"));
+    }
+}
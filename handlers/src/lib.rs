@@ -3,17 +3,69 @@ use axum::extract::Query;
 use axum::http::StatusCode;
 use axum::{extract::State, Json};
 use databases::bugcheck::BugCheckCache;
-use databases::cpu::Cpu;
-use databases::{cpu::CpuCache, pcie::PcieCache, usb::UsbCache};
+use databases::cpu::{
+    AttributeSection, CatalogEntry, Cpu, CpuComparison, CpuFilter, CpuLookupError, CpuSortKey,
+    CpuVendor, ScoreExplanation, Segment, SortOrder, STRICT_CONFIDENCE_THRESHOLD,
+};
+use databases::{
+    cpu::CpuCache,
+    pcie::{PcieCache, PcieDeviceInfo},
+    usb::{UsbCache, UsbDeviceInfo},
+};
+use parsing::identifier::IdentifierError;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tracing::{error, warn};
 
 #[derive(Clone)]
+/// The caches are read-only after startup, so they're shared between requests instead of being cloned for each one
 pub struct AppState {
-    pub cpu_cache: CpuCache,
-    pub usb_cache: UsbCache,
-    pub pcie_cache: PcieCache,
-    pub bugcheck_cache: BugCheckCache,
+    pub cpu_cache: Arc<CpuCache>,
+    pub usb_cache: Arc<UsbCache>,
+    pub pcie_cache: Arc<PcieCache>,
+    pub bugcheck_cache: Arc<BugCheckCache>,
+}
+
+/// The body returned alongside an error status code when a device identifier or cpu can't be looked up
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ErrorResponse {
+    /// A short, stable name for the error, like `non_hex_digit` or `unknown_vendor`
+    pub kind: String,
+    /// A human readable description of the error
+    pub message: String,
+}
+
+/// Malformed identifiers are the fault of the client, so they're reported as `400 Bad Request`,
+/// while identifiers that just aren't in the database are reported as `404 Not Found`
+fn identifier_error_response(e: IdentifierError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = if e.is_malformed() {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    (
+        status,
+        Json(ErrorResponse {
+            kind: e.kind().to_string(),
+            message: e.to_string(),
+        }),
+    )
+}
+
+/// A cpu whose vendor can't be determined is rejected with `400 Bad Request` rather than guessed at,
+/// while any other failure means there's no match, and is reported as `404 Not Found`
+fn cpu_error_response(e: CpuLookupError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match e {
+        CpuLookupError::UnknownVendor(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::NOT_FOUND,
+    };
+    (
+        status,
+        Json(ErrorResponse {
+            kind: e.kind().to_string(),
+            message: e.to_string(),
+        }),
+    )
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -25,6 +77,35 @@ pub struct UsbQuery {
 pub struct UsbResponse {
     pub vendor: Option<String>,
     pub device: Option<String>,
+    /// The enumerator the identifier was read from, like `USB`, `HID` or `BTHENUM`
+    pub enumerator: Option<String>,
+    /// The device release number read from the `REV_` field of the identifier, formatted like `1.00`
+    pub revision: Option<String>,
+    /// The interface number of a composite device, read from the `MI_` field of the identifier
+    pub interface: Option<u8>,
+    /// The device class, read from compatible IDs like `USB\Class_03&SubClass_01&Prot_02` or from the class fields of a
+    /// modalias string
+    pub class: Option<String>,
+    pub subclass: Option<String>,
+    pub protocol: Option<String>,
+}
+
+impl From<UsbDeviceInfo<'_>> for UsbResponse {
+    fn from(info: UsbDeviceInfo<'_>) -> Self {
+        Self {
+            vendor: info.vendor.map(String::from),
+            device: info.device.map(String::from),
+            enumerator: info.enumerator.map(|e| e.to_ascii_uppercase()),
+            // the revision is binary coded decimal, so 0x0110 is version 1.10
+            revision: info
+                .revision
+                .map(|rev| format!("{:x}.{:02x}", rev >> 8, rev & 0xff)),
+            interface: info.interface,
+            class: info.class.map(|c| c.class.to_string()),
+            subclass: info.class.and_then(|c| c.subclass).map(String::from),
+            protocol: info.class.and_then(|c| c.protocol).map(String::from),
+        }
+    }
 }
 
 /// This handler accepts a `GET` request to `/api/usbs/?identifier`.
@@ -33,21 +114,135 @@ pub struct UsbResponse {
 pub async fn get_usb_handler(
     State(state): State<AppState>,
     Query(query): Query<UsbQuery>,
-) -> Result<Json<UsbResponse>, StatusCode> {
-    // TODO: update docs
+) -> Result<Json<UsbResponse>, (StatusCode, Json<ErrorResponse>)> {
     let results = state.usb_cache.find(&query.identifier);
     match results {
-        Ok(r) => Ok(Json(UsbResponse {
-            vendor: r.0.map(|v| v.name),
-            device: r.1.map(|d| d.name),
-        })),
+        Ok(r) => Ok(Json(UsbResponse::from(r))),
         Err(e) => {
             error!("usb handler error: {:?} caused by query: {:?}", e, query);
-            Err(StatusCode::NOT_FOUND)
+            Err(identifier_error_response(e))
         }
     }
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HidUsageQuery {
+    #[serde(deserialize_with = "deserialize_id")]
+    pub usage_page: u16,
+    #[serde(default, deserialize_with = "deserialize_optional_id")]
+    pub usage: Option<u16>,
+}
+
+/// Read a numeric id from a query parameter. usb.ids and the USB specs write ids in hex, so a value prefixed with `0x`,
+/// with a leading zero (like `0409`), or containing a hex letter (like `C0`) is read as hex, anything else as decimal
+fn parse_id(value: &str) -> Option<u16> {
+    let value = value.trim();
+    if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        return u16::from_str_radix(hex, 16).ok();
+    }
+    if (value.len() > 1 && value.starts_with('0'))
+        || value.contains(|c: char| c.is_ascii_hexdigit() && !c.is_ascii_digit())
+    {
+        return u16::from_str_radix(value, 16).ok();
+    }
+    value.parse().ok()
+}
+
+fn deserialize_id<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u16, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_id(&value).ok_or_else(|| {
+        serde::de::Error::custom(format!("expected a decimal or hex id, found {value:?}"))
+    })
+}
+
+fn deserialize_optional_id<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u16>, D::Error> {
+    deserialize_id(deserializer).map(Some)
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HidUsageResponse {
+    pub usage_page: String,
+    pub usage: Option<String>,
+}
+
+/// This handler accepts a `GET` request to `/api/usb/hid/?usage_page=[USAGE_PAGE]&usage=[USAGE]`.
+/// It relies on a globally shared [AppState] to re-use the usb cache, and responds with the names of the HID usage page and usage.
+#[tracing::instrument(name = "hid_usage_handler", skip(state))]
+pub async fn get_hid_usage_handler(
+    State(state): State<AppState>,
+    Query(query): Query<HidUsageQuery>,
+) -> Result<Json<HidUsageResponse>, StatusCode> {
+    match state
+        .usb_cache
+        .find_hid_usage(query.usage_page, query.usage)
+    {
+        Some((page, usage)) => Ok(Json(HidUsageResponse {
+            usage_page: page.to_string(),
+            usage: usage.map(String::from),
+        })),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UsbLanguageQuery {
+    #[serde(deserialize_with = "deserialize_id")]
+    pub id: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UsbLanguageResponse {
+    pub language: String,
+    pub dialect: Option<String>,
+}
+
+/// This handler accepts a `GET` request to `/api/usb/language/?id=[LANGUAGE_ID]`.
+/// It relies on a globally shared [AppState] to re-use the usb cache, and responds with the language and dialect of a USB language ID.
+#[tracing::instrument(name = "usb_language_handler", skip(state))]
+pub async fn get_usb_language_handler(
+    State(state): State<AppState>,
+    Query(query): Query<UsbLanguageQuery>,
+) -> Result<Json<UsbLanguageResponse>, StatusCode> {
+    match state.usb_cache.find_language(query.id) {
+        Some((language, dialect)) => Ok(Json(UsbLanguageResponse {
+            language: language.to_string(),
+            dialect: dialect.map(String::from),
+        })),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AudioTerminalQuery {
+    #[serde(deserialize_with = "deserialize_id")]
+    pub id: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AudioTerminalResponse {
+    pub name: String,
+}
+
+/// This handler accepts a `GET` request to `/api/usb/audio-terminal/?id=[TERMINAL_TYPE]`.
+/// It relies on a globally shared [AppState] to re-use the usb cache, and responds with the name of a USB audio terminal type.
+#[tracing::instrument(name = "audio_terminal_handler", skip(state))]
+pub async fn get_audio_terminal_handler(
+    State(state): State<AppState>,
+    Query(query): Query<AudioTerminalQuery>,
+) -> Result<Json<AudioTerminalResponse>, StatusCode> {
+    match state.usb_cache.find_audio_terminal(query.id) {
+        Some(name) => Ok(Json(AudioTerminalResponse {
+            name: name.to_string(),
+        })),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GetPcieQuery {
     identifier: String,
@@ -58,6 +253,27 @@ pub struct PcieResponse {
     pub vendor: Option<String>,
     pub device: Option<String>,
     pub subsystem: Option<String>,
+    /// The vendor that built the card or board, taken from the subsystem vendor ID
+    pub subsystem_vendor: Option<String>,
+    /// The device class, only available if the identifier has a `CC_` class code
+    pub class: Option<String>,
+    pub subclass: Option<String>,
+    pub prog_if: Option<String>,
+}
+
+impl From<PcieDeviceInfo<'_>> for PcieResponse {
+    fn from(info: PcieDeviceInfo<'_>) -> Self {
+        let (vendor, device, subsystem, subsystem_vendor, class) = info;
+        Self {
+            vendor: vendor.map(String::from),
+            device: device.map(String::from),
+            subsystem: subsystem.map(String::from),
+            subsystem_vendor: subsystem_vendor.map(String::from),
+            class: class.map(|c| c.class.to_string()),
+            subclass: class.and_then(|c| c.subclass).map(String::from),
+            prog_if: class.and_then(|c| c.prog_if).map(String::from),
+        }
+    }
 }
 
 /// This handler accepts a `GET` request to `/api/pcie/?identifier`.
@@ -66,17 +282,13 @@ pub struct PcieResponse {
 pub async fn get_pcie_handler(
     State(state): State<AppState>,
     Query(query): Query<GetPcieQuery>,
-) -> Result<Json<PcieResponse>, StatusCode> {
+) -> Result<Json<PcieResponse>, (StatusCode, Json<ErrorResponse>)> {
     let results = state.pcie_cache.find(&query.identifier);
     match results {
-        Ok(r) => Ok(Json(PcieResponse {
-            vendor: r.0.map(|v| v.name),
-            device: r.1.map(|d| d.name),
-            subsystem: r.2.map(|s| s.name),
-        })),
+        Ok(r) => Ok(Json(PcieResponse::from(r))),
         Err(e) => {
             error!("pcie handler error: {:?} caused by query: {:?}", e, query);
-            Err(StatusCode::NOT_FOUND)
+            Err(identifier_error_response(e))
         }
     }
 }
@@ -92,11 +304,7 @@ pub async fn post_pcie_handler(
     let mut response: Vec<Option<PcieResponse>> = Vec::with_capacity(16);
     for entry in query {
         match state.pcie_cache.find(&entry) {
-            Ok(r) => response.push(Some(PcieResponse {
-                vendor: r.0.map(|v| v.name),
-                device: r.1.map(|d| d.name),
-                subsystem: r.2.map(|s| s.name),
-            })),
+            Ok(r) => response.push(Some(PcieResponse::from(r))),
             Err(e) => {
                 warn!("post pcie handler error: when processing the device identifier {:?}, an error was returned: {:?}", entry, e);
                 response.push(None);
@@ -117,10 +325,7 @@ pub async fn post_usbs_handler(
     let mut response: Vec<Option<UsbResponse>> = Vec::with_capacity(16);
     for entry in query {
         match state.usb_cache.find(&entry) {
-            Ok(r) => response.push(Some(UsbResponse {
-                vendor: r.0.map(|v| v.name),
-                device: r.1.map(|d| d.name),
-            })),
+            Ok(r) => response.push(Some(UsbResponse::from(r))),
             Err(e) => {
                 warn!("post usb handler error: when processing the device identifier {:?}, an error was returned: {:?}", entry, e);
                 response.push(None);
@@ -133,32 +338,270 @@ pub async fn post_usbs_handler(
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CpuQuery {
     pub name: String,
+    /// Return up to this many ranked matches instead of only the best one
+    pub candidates: Option<usize>,
+    /// Include how each match was scored, implies a list of candidates is returned
+    #[serde(default)]
+    pub explain: bool,
+    /// Reject matches with a confidence below [STRICT_CONFIDENCE_THRESHOLD]
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// A possible match for a cpu lookup, along with how confident the match is
+#[derive(Debug, Serialize)]
+pub struct CpuCandidateResponse {
+    #[serde(flatten)]
+    pub cpu: Cpu<String>,
+    pub confidence: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ScoreExplanation>,
+}
+
+/// A single cpu is returned unless candidates or an explanation were requested
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum CpuResponse {
+    Cpu(Box<Cpu<String>>),
+    Candidates(Vec<CpuCandidateResponse>),
 }
 
 /// This handler accepts a `GET` request to `/api/cpus/?name=[CPU_NAME]`.
 /// It relies on a globally shared [AppState] to re-use the cpu cache, and responds to the request with a serialized [Cpu].
-/// It will always attempt to find a cpu, and should always return a cpu. The correctness of the return value is not guaranteed.
+/// If `candidates` or `explain` are set, a list of ranked matches with their confidence is returned instead.
+/// Unless `strict` is set, it will always attempt to find a cpu, so the correctness of the best match is not guaranteed.
 #[tracing::instrument(name = "cpu_handler", skip(state))]
 pub async fn get_cpu_handler(
-    State(mut state): State<AppState>,
+    State(state): State<AppState>,
     Query(query): Query<CpuQuery>,
-) -> Result<Json<Cpu<String>>, StatusCode> {
-    match state.cpu_cache.find(&query.name) {
-        Ok(c) => Ok(Json(Cpu {
-            name: c.name.to_string(),
-            attributes: c
-                .attributes
-                .iter()
-                .map(|(k, v)| (k.to_string(), v.to_string()))
-                .collect(),
-        })),
+) -> Result<Json<CpuResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let count = query.candidates.unwrap_or(1).max(1);
+    let mut candidates = match state.cpu_cache.find_candidates(&query.name, count) {
+        Ok(c) => c,
         Err(e) => {
             error!("cpu handler error {:?} caused by query {:?}", e, query);
-            Err(StatusCode::NOT_FOUND)
+            return Err(cpu_error_response(e));
+        }
+    };
+    if query.strict {
+        candidates.retain(|c| c.confidence >= STRICT_CONFIDENCE_THRESHOLD);
+        if candidates.is_empty() {
+            warn!(
+                "cpu handler: no matches met the confidence threshold for query {:?}",
+                query
+            );
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    kind: String::from("low_confidence"),
+                    message: format!(
+                        "no matches had a confidence of at least {STRICT_CONFIDENCE_THRESHOLD}"
+                    ),
+                }),
+            ));
+        }
+    }
+    if query.candidates.is_none() && !query.explain {
+        return Ok(Json(CpuResponse::Cpu(Box::new(owned_cpu(
+            candidates.remove(0).cpu,
+        )))));
+    }
+    Ok(Json(CpuResponse::Candidates(
+        candidates
+            .into_iter()
+            .map(|c| CpuCandidateResponse {
+                cpu: owned_cpu(c.cpu),
+                confidence: c.confidence,
+                explanation: query.explain.then_some(c.explanation),
+            })
+            .collect(),
+    )))
+}
+
+/// This handler accepts a `POST` request to `/api/cpus/`, with a body containing a serialized array of cpu name strings.
+/// It relies on a globally shared [AppState] to re-use the cpu cache, and is largely identical to [get_cpu_handler], but
+/// is intended for batching
+#[tracing::instrument(name = "bulk_cpu_handler", skip(state))]
+pub async fn post_cpus_handler(
+    State(state): State<AppState>,
+    Json(query): Json<Vec<String>>,
+) -> Result<Json<Vec<Option<Cpu<String>>>>, StatusCode> {
+    let mut response: Vec<Option<Cpu<String>>> = Vec::with_capacity(16);
+    for entry in query {
+        match state.cpu_cache.find(&entry) {
+            Ok(c) => response.push(Some(owned_cpu(c))),
+            Err(e) => {
+                warn!("post cpu handler error: when processing the cpu name {:?}, an error was returned: {:?}", entry, e);
+                response.push(None);
+            }
+        }
+    }
+    Ok(Json(response))
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CpuPartQuery {
+    pub id: String,
+}
+
+/// This handler accepts a `GET` request to `/api/cpus/by-part/?id=[PART_NUMBER]`.
+/// It relies on a globally shared [AppState] to re-use the cpu cache, and responds with the serialized [Cpu] that has
+/// the exact AMD product ID or intel processor number that was searched for.
+#[tracing::instrument(name = "cpu_part_handler", skip(state))]
+pub async fn get_cpu_by_part_handler(
+    State(state): State<AppState>,
+    Query(query): Query<CpuPartQuery>,
+) -> Result<Json<Cpu<String>>, (StatusCode, Json<ErrorResponse>)> {
+    match state.cpu_cache.find_by_part(&query.id) {
+        Ok(cpu) => Ok(Json(owned_cpu(cpu))),
+        Err(e) => {
+            error!("cpu part handler error {:?} caused by query {:?}", e, query);
+            Err(cpu_error_response(e))
         }
     }
 }
 
+/// The most cpus that can be returned in a single page of search results
+const MAX_SEARCH_LIMIT: usize = 200;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CpuSearchQuery {
+    pub vendor: Option<CpuVendor>,
+    pub family: Option<String>,
+    pub socket: Option<String>,
+    pub min_cores: Option<u32>,
+    pub max_cores: Option<u32>,
+    pub launch_year: Option<u16>,
+    pub segment: Option<Segment>,
+    #[serde(default)]
+    pub sort: CpuSortKey,
+    #[serde(default)]
+    pub order: SortOrder,
+    #[serde(default)]
+    pub offset: usize,
+    /// Defaults to 50, and is capped at [MAX_SEARCH_LIMIT]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CpuSearchResponse {
+    /// How many cpus matched the filters, across every page
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub cpus: Vec<CatalogEntry>,
+}
+
+/// This handler accepts a `GET` request to `/api/cpus/search/`, with any combination of filters as query parameters.
+/// It relies on a globally shared [AppState] to re-use the cpu cache, and responds with a page of the cpus that match
+/// every filter, along with their specs.
+#[tracing::instrument(name = "cpu_search_handler", skip(state))]
+pub async fn get_cpu_search_handler(
+    State(state): State<AppState>,
+    Query(query): Query<CpuSearchQuery>,
+) -> Json<CpuSearchResponse> {
+    let limit = query.limit.unwrap_or(50).min(MAX_SEARCH_LIMIT);
+    let filter = CpuFilter {
+        vendor: query.vendor,
+        family: query.family,
+        socket: query.socket,
+        min_cores: query.min_cores,
+        max_cores: query.max_cores,
+        launch_year: query.launch_year,
+        segment: query.segment,
+    };
+    let results = state
+        .cpu_cache
+        .search(&filter, query.sort, query.order, query.offset, limit);
+    Json(CpuSearchResponse {
+        total: results.total,
+        offset: query.offset,
+        limit,
+        cpus: results.cpus.into_iter().cloned().collect(),
+    })
+}
+
+/// The most names that can be suggested for a single query
+const MAX_SUGGEST_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CpuSuggestQuery {
+    pub q: String,
+    /// Defaults to 10, and is capped at [MAX_SUGGEST_LIMIT]
+    pub limit: Option<usize>,
+}
+
+/// This handler accepts a `GET` request to `/api/cpus/suggest/?q=[PARTIAL_NAME]`.
+/// It relies on a globally shared [AppState] to re-use the cpu cache, and responds with the names of the cpus that best
+/// match the partially typed name, intended for autocomplete.
+#[tracing::instrument(name = "cpu_suggest_handler", skip(state))]
+pub async fn get_cpu_suggest_handler(
+    State(state): State<AppState>,
+    Query(query): Query<CpuSuggestQuery>,
+) -> Json<Vec<&'static str>> {
+    let limit = query.limit.unwrap_or(10).min(MAX_SUGGEST_LIMIT);
+    Json(state.cpu_cache.suggest(&query.q, limit))
+}
+
+/// This handler accepts a `POST` request to `/api/cpus/compare/`, with a body containing a serialized array of two to
+/// four cpu name strings. It relies on a globally shared [AppState] to re-use the cpu cache, and responds with the
+/// attributes of every cpu lined up, the differences between their specs, and the features only some of them have.
+/// If any of the cpus can't be found, the error for the first one is returned
+#[tracing::instrument(name = "cpu_compare_handler", skip(state))]
+pub async fn post_cpu_compare_handler(
+    State(state): State<AppState>,
+    Json(query): Json<Vec<String>>,
+) -> Result<Json<CpuComparison>, (StatusCode, Json<ErrorResponse>)> {
+    if !(2..=4).contains(&query.len()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                kind: String::from("invalid_cpu_count"),
+                message: format!(
+                    "2 to 4 cpus can be compared, but {} were given",
+                    query.len()
+                ),
+            }),
+        ));
+    }
+    let names: Vec<&str> = query.iter().map(String::as_str).collect();
+    match state.cpu_cache.compare(&names) {
+        Ok(comparison) => Ok(Json(comparison)),
+        Err(e) => {
+            error!(
+                "cpu compare handler error {:?} caused by query {:?}",
+                e, query
+            );
+            Err(cpu_error_response(e))
+        }
+    }
+}
+
+/// Copy a cpu out of the database so that it can be serialized
+fn owned_cpu(cpu: Cpu<&str>) -> Cpu<String> {
+    Cpu {
+        name: cpu.name.to_string(),
+        attributes: cpu
+            .attributes
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        sections: cpu
+            .sections
+            .iter()
+            .map(|section| AttributeSection {
+                name: section.name.to_string(),
+                attributes: section
+                    .attributes
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            })
+            .collect(),
+        spec: cpu.spec,
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GetBugCheckQuery {
     code: u64,
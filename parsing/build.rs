@@ -0,0 +1,39 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const INTEL_CHUNK_DIR: &str = "src/cpu/intel/chunks";
+
+fn main() {
+    println!("cargo::rerun-if-changed={INTEL_CHUNK_DIR}");
+    println!("cargo::rerun-if-changed=build.rs");
+    gen_intel_chunks();
+}
+
+/// Write an array of every intel ARK export in the chunk directory to `$OUT_DIR/intel_chunks.rs`, so that newer
+/// exports can be dropped in without editing the list by hand. Chunks are ordered by the number in their file name
+fn gen_intel_chunks() {
+    let chunk_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(INTEL_CHUNK_DIR);
+    let mut chunks: Vec<(u32, String)> = fs::read_dir(&chunk_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "csv"))
+        .map(|path| {
+            let number = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse().ok())
+                .unwrap_or_else(|| panic!("intel chunk {path:?} should be named like `17.csv`"));
+            (number, path.to_str().unwrap().to_string())
+        })
+        .collect();
+    chunks.sort();
+
+    let mut generated = format!("const CHUNKS: [&str; {}] = [\n", chunks.len());
+    for (_, path) in chunks {
+        generated.push_str(&format!("    include_str!({path:?}),\n"));
+    }
+    generated.push_str("];\n");
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("intel_chunks.rs");
+    fs::write(out_path, generated).unwrap();
+}
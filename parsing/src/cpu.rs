@@ -5,9 +5,11 @@ use serde::Serialize;
 use tracing::{debug, error};
 mod amd;
 mod intel;
+mod qualcomm;
 
 use amd::get_amd_cpus;
 use intel::get_intel_cpus;
+use qualcomm::get_qualcomm_cpus;
 
 /// A generic representation of a cpu. T is the string type, there are massive gains by using zero copy for the intel cpu database, but that's a lot more work
 /// for the amd CPU database.
@@ -17,8 +19,21 @@ use intel::get_intel_cpus;
 pub struct Cpu<T> {
     /// Something like "Intel core i5-1234 processor"
     pub name: T,
-    /// A list of attributes, examples might include a core count of 8, or whether or not a certain feature is enabled
+    /// A list of attributes, examples might include a core count of 8, or whether or not a certain feature is enabled.
+    /// These are the same attributes as in `sections`, indexed by label for lookups, so they aren't serialized
+    #[serde(skip)]
     pub attributes: HashMap<T, T>,
+    /// The attributes, grouped into sections and kept in the order the vendor lists them
+    pub sections: Vec<AttributeSection<T>>,
+}
+
+/// A group of attributes listed under the same header, like "Essentials" or "Memory Specifications" on an ARK page
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AttributeSection<T> {
+    /// The header of the section, like "CPU Specifications"
+    pub name: T,
+    /// Labels paired with their values, in the order they're listed
+    pub attributes: Vec<(T, T)>,
 }
 
 #[derive(PartialEq, Clone)]
@@ -46,6 +61,8 @@ pub struct IntermediateCpuCache<'a> {
     intel_index: Vec<IndexEntry>,
     pub amd_cpus: Vec<Cpu<&'a str>>,
     amd_index: Vec<IndexEntry>,
+    /// Qualcomm cpus are only used by the build script, so they aren't indexed
+    pub qualcomm_cpus: Vec<Cpu<&'a str>>,
 }
 
 impl IntermediateCpuCache<'_> {
@@ -69,7 +86,7 @@ impl IntermediateCpuCache<'_> {
         debug!("Amd CPU list deserialized");
         let mut amd_index: Vec<IndexEntry> = Vec::with_capacity(2048);
         for (i, cpu) in amd_cpus.iter().enumerate() {
-            match generate_index_entry(&cpu.name, i) {
+            match generate_index_entry(cpu.name, i) {
                 Ok(idx) => {
                     amd_index.push(idx);
                 }
@@ -84,12 +101,15 @@ impl IntermediateCpuCache<'_> {
         amd_cpus.shrink_to_fit();
         intel_index.shrink_to_fit();
         amd_index.shrink_to_fit();
+        let qualcomm_cpus = get_qualcomm_cpus();
+        debug!("Qualcomm CPU list deserialized");
 
         Self {
             intel_cpus,
             intel_index,
             amd_cpus,
             amd_index,
+            qualcomm_cpus,
         }
     }
 
@@ -143,14 +163,11 @@ impl IntermediateCpuCache<'_> {
                 Err(Box::from("No close matches found"))
             }
             Some(idx_entry) => {
-                let found_cpu: &Cpu<&str>;
-
-                if input.contains("AMD") {
-                    found_cpu= &self.amd_cpus[idx_entry.index];
-                }
-                else {
-                    found_cpu = &self.intel_cpus[idx_entry.index];
-                }
+                let found_cpu: &Cpu<&str> = if input.contains("AMD") {
+                    &self.amd_cpus[idx_entry.index]
+                } else {
+                    &self.intel_cpus[idx_entry.index]
+                };
                 Ok(Cpu {
                     name: found_cpu.name.to_string(),
                     attributes: found_cpu
@@ -158,6 +175,18 @@ impl IntermediateCpuCache<'_> {
                         .iter()
                         .map(|(k, v)| (k.to_string(), v.to_string()))
                         .collect(),
+                    sections: found_cpu
+                        .sections
+                        .iter()
+                        .map(|section| AttributeSection {
+                            name: section.name.to_string(),
+                            attributes: section
+                                .attributes
+                                .iter()
+                                .map(|(k, v)| (k.to_string(), v.to_string()))
+                                .collect(),
+                        })
+                        .collect(),
                 })
             }
         }
@@ -12,13 +12,13 @@ pub struct LexerOutput<'a> {
     /// Names of all attributes
     pub header: Vec<&'a str>,
     /// A list of all cpus in the file
-    pub cpus: Vec<Vec<&'a str>>
+    pub cpus: Vec<Vec<&'a str>>,
 }
 
 pub fn lex_csv<'a>(input: &'a str) -> Result<LexerOutput<'a>, Box<dyn Error + 'a>> {
     let header_combinator_output = read_file_header(input).expect("Failed to read AMD CSV header");
     let cpu_combinator_output = read_record(input).expect("Failed to read AMD CPU data");
-    
+
     Ok(LexerOutput {
         header: header_combinator_output.1,
         cpus: cpu_combinator_output.1,
@@ -28,23 +28,22 @@ pub fn lex_csv<'a>(input: &'a str) -> Result<LexerOutput<'a>, Box<dyn Error + 'a
 /// Header of CSV contains the title of each data entry
 fn read_file_header(input: &str) -> IResult<&str, Vec<&str>> {
     // stripping BOM bytes from start of file
-    let header_line = delimited(
-        tag("\u{feff}"),
-        take_until("\n"),
-        tag("\n"),
-    )(input)?;
+    let header_line = delimited(tag("\u{feff}"), take_until("\n"), tag("\n"))(input)?;
 
-    let split_input: Vec<&str> = header_line.1.split("\",\"").map(|s| s.trim_matches(|c| c == '"' || c == ',' || c == '\n')).collect();
+    let split_input: Vec<&str> = header_line
+        .1
+        .split("\",\"")
+        .map(|s| s.trim_matches(|c| c == '"' || c == ',' || c == '\n'))
+        .collect();
 
     Ok(("", split_input))
 }
 
 // CPUs are formatted according to the header
 fn read_record(input: &str) -> IResult<&str, Vec<Vec<&str>>> {
-
     // Removing the header from the input
     // File terminates with double newlines
-    let cpu_records= delimited(
+    let cpu_records = delimited(
         // read a whole line, this is discarded
         terminated(take_until::<_, _, nom::error::Error<_>>("\n"), tag("\n")),
         // read until two newlines in a row
@@ -57,7 +56,10 @@ fn read_record(input: &str) -> IResult<&str, Vec<Vec<&str>>> {
     let mut cpu_record: Vec<Vec<&str>> = Vec::with_capacity(1024);
 
     for cpu in cpu_list {
-        let cpu_vector: Vec<&str> = cpu.split("\",\"").map(|s| s.trim_matches(|c| c == '"' || c == ',' || c == '\n')).collect();
+        let cpu_vector: Vec<&str> = cpu
+            .split("\",\"")
+            .map(|s| s.trim_matches(|c| c == '"' || c == ',' || c == '\n'))
+            .collect();
         cpu_record.push(cpu_vector);
     }
 
@@ -81,4 +83,4 @@ mod tests {
         let output = read_record(mock_record).unwrap();
         assert_eq!(output.1[0][2], "Ryzen AI PRO 400 Series");
     }
-}
\ No newline at end of file
+}
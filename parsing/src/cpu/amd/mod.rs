@@ -1,4 +1,4 @@
-use self::parser::parse_csv;
+pub(super) use self::parser::parse_csv;
 use super::Cpu;
 
 mod lexer;
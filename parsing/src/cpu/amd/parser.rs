@@ -1,5 +1,5 @@
 use super::lexer::lex_csv;
-use crate::cpu::Cpu;
+use crate::cpu::{AttributeSection, Cpu};
 use std::collections::HashMap;
 use std::error::Error;
 
@@ -8,22 +8,29 @@ pub fn parse_csv(csv: &'_ str) -> Result<Vec<Cpu<&str>>, Box<dyn Error + '_>> {
     // the returned value
     let mut output: Vec<Cpu<&str>> = Vec::new();
     for cpu_data in lexer_output.cpus {
-
         let mut cpu: Cpu<&str> = Cpu {
             // First entry is always name
             name: cpu_data[0],
             attributes: HashMap::new(),
+            sections: Vec::new(),
         };
+        // the export isn't split into sections, so every attribute goes into one, in column order
+        let mut attributes: Vec<(&str, &str)> = Vec::new();
 
         for (index, &entry) in lexer_output.header.iter().enumerate() {
             // Skip name entry, name is already provided
             if entry != "Name" && !cpu_data[index].is_empty() {
-                cpu.attributes.insert(lexer_output.header[index], cpu_data[index]);
+                cpu.attributes
+                    .insert(lexer_output.header[index], cpu_data[index]);
+                attributes.push((lexer_output.header[index], cpu_data[index]));
             }
         }
+        cpu.sections.push(AttributeSection {
+            name: "Specifications",
+            attributes,
+        });
 
         output.push(cpu);
-        
     }
 
     Ok(output)
@@ -1,12 +1,11 @@
-use core::slice;
 use std::error::Error;
 
+use nom::branch::alt;
 use nom::bytes::complete::{tag, take_until};
+use nom::combinator::rest;
 use nom::sequence::{delimited, terminated};
 use nom::IResult;
 
-type NomError<'a> = nom::Err<nom::error::Error<&'a str>>;
-
 /// Everything deserialized by the lexer
 #[derive(Debug)]
 #[allow(unused)]
@@ -15,7 +14,15 @@ pub struct LexerOutput<'a> {
     pub timestamp: &'a str,
     /// A list of all cpus in the file
     pub cpus: Vec<&'a str>,
-    /// the deserialized records in the file
+    /// The sections in the file, in the order they're listed
+    pub sections: Vec<Section<'a>>,
+}
+
+/// A group of records underneath a header, like "Essentials" or "CPU Specifications"
+#[derive(Debug, PartialEq)]
+pub struct Section<'a> {
+    pub name: &'a str,
+    /// The deserialized records in the section, the first item in each record is the label
     pub records: Vec<Vec<&'a str>>,
 }
 
@@ -24,29 +31,27 @@ pub fn lex_csv<'a>(input: &'a str) -> Result<LexerOutput<'a>, Box<dyn Error + 'a
     let cpu_combinator_output =
         read_cpu_record(header_combinator_output.0).expect("Failed to parse list of cpus");
 
-    // sections are now repeatedly read from the csv until no more can be read
-    let mut serialized_records: Vec<&str> = Vec::with_capacity(256);
-    let mut iterated_output =
-        read_section(cpu_combinator_output.0).expect("Failed to read first section");
-    while !iterated_output.0.is_empty() {
-        serialized_records.push(iterated_output.1);
-        iterated_output = read_section(iterated_output.0)?;
-    }
-    // iterate over all of the serialized records and deserialize them
-    let mut deserialized_records: Vec<Vec<&str>> = Vec::with_capacity(256);
-    for serialized_record in serialized_records {
-        // this variable is iterated over until all sections are read from
-        let mut iterated_output = read_record(serialized_record)?;
-        while !iterated_output.0.is_empty() {
-            deserialized_records.push(iterated_output.1);
-            iterated_output = read_record(iterated_output.0)?;
+    // sections are now repeatedly read from the csv until the end of the file
+    let mut sections: Vec<Section> = Vec::with_capacity(16);
+    let mut remaining = cpu_combinator_output.0;
+    while !remaining.is_empty() {
+        let (after_section, (name, serialized_records)) = read_section(remaining)?;
+        remaining = after_section;
+        // iterate over all of the serialized records and deserialize them
+        let mut records: Vec<Vec<&str>> = Vec::with_capacity(32);
+        let mut serialized_records = serialized_records;
+        while !serialized_records.is_empty() {
+            let (after_record, record) = read_record(serialized_records)?;
+            serialized_records = after_record;
+            records.push(record);
         }
+        sections.push(Section { name, records });
     }
 
     Ok(LexerOutput {
         timestamp: header_combinator_output.1,
         cpus: cpu_combinator_output.1,
-        records: deserialized_records,
+        sections,
     })
 }
 
@@ -69,46 +74,19 @@ fn read_cpu_record(input: &str) -> IResult<&str, Vec<&str>> {
     Ok((cpu_record.0.strip_prefix(" \n").unwrap(), split_input))
 }
 
+/// Read a single line of comma separated values, the last line in a section doesn't end in a newline
 fn read_record(input: &str) -> IResult<&str, Vec<&str>> {
-    let record: (&str, &str) = take_until("\n")(input)?;
-    let split_input: Vec<&str> = record.1.split(',').collect();
-    // .strip_prefix is used because take_until includes the "until" part
-    // in the remainder instead of dropping it
-    Ok((record.0.strip_prefix('\n').unwrap(), split_input))
+    let (remaining, record) = alt((terminated(take_until("\n"), tag("\n")), rest))(input)?;
+    Ok((remaining, record.split(',').collect()))
 }
 
-/// The majority of the actual data organized into sections, this function reads a "block", or
-/// everything underneath a header, returning a serialized group of records
-fn read_section(input: &str) -> IResult<&str, &str> {
-    // read a line and discard it, the actual "header", then read until there are two newlines in a row
-    // this is nasty and could definitely be cleaned up a lot
-    let combinator_output: Result<(&str, &str), NomError> = delimited(
-        // read a whole line, this is discarded
-        terminated(take_until::<_, _, nom::error::Error<_>>("\n"), tag("\n")),
-        // read until two newlines in a row
-        // preceded(tag("\n"), take_until("\n\n")),
-        take_until("\n\n"),
-        tag("\n\n"),
-    )(input);
-    // the very last section in the file needs special handling because it does not have two newlines at the end
-    // this is probably fallible and not ideal
-    match combinator_output {
-        Ok(o) => {
-            // because there's not really a "take_until_inclusive"
-            // TODO: take a look at the nom source code for take_until, and write
-            // a combinator for `take_until_incl` or whatever
-            let raw_ptr: *const u8 = o.1.as_ptr();
-            let str_len = o.1.len();
-            // SAFETY: the combinator above asserts that there's at least one `\n`
-            // in front of the subslice
-            let output_str: &str = unsafe {
-                let slice: &[u8] = slice::from_raw_parts(raw_ptr, str_len + 1);
-                std::str::from_utf8(slice).unwrap()
-            };
-            Ok((o.0, output_str))
-        }
-        Err(_) => Ok(("", input)),
-    }
+/// The majority of the actual data organized into sections, this function reads a "block", or everything underneath
+/// a header, returning the header and a serialized group of records. Sections are separated by a blank line, and the
+/// last section in the file runs until the end of the file
+fn read_section(input: &str) -> IResult<&str, (&str, &str)> {
+    let (remaining, header) = terminated(take_until("\n"), tag("\n"))(input)?;
+    let (remaining, records) = alt((terminated(take_until("\n\n"), tag("\n\n")), rest))(remaining)?;
+    Ok((remaining, (header, records)))
 }
 
 #[cfg(test)]
@@ -140,6 +118,26 @@ mod tests {
     fn basic_read_section() {
         let mock_section = "heading\nab\ncd\n\notherheading";
         let output = read_section(mock_section);
-        assert_eq!(output, Ok(("otherheading", "ab\ncd\n")));
+        assert_eq!(output, Ok(("otherheading", ("heading", "ab\ncd"))));
+    }
+
+    #[test]
+    fn read_last_section() {
+        let mock_file = "\u{feff}ARK | Intel® Product Specification Comparison\n01/14/2024 01:06:53 PM\n ,cpu a ,cpu b \nEssentials\nab,1,2\ncd,3,4\n\nOther\nef,5,6\n";
+        let output = lex_csv(mock_file).unwrap();
+        assert_eq!(output.cpus, ["cpu a", "cpu b"]);
+        assert_eq!(
+            output.sections,
+            [
+                Section {
+                    name: "Essentials",
+                    records: vec![vec!["ab", "1", "2"], vec!["cd", "3", "4"]]
+                },
+                Section {
+                    name: "Other",
+                    records: vec![vec!["ef", "5", "6"]]
+                }
+            ]
+        );
     }
 }
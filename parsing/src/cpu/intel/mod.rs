@@ -43,25 +43,9 @@ for (const chunk of chunks) {
 ```
 */
 
-// this list should contain every intel processor till the beginning of 2024
-const CHUNKS: [&str; 16] = [
-    include_str!("chunks/1.csv"),
-    include_str!("chunks/2.csv"),
-    include_str!("chunks/3.csv"),
-    include_str!("chunks/4.csv"),
-    include_str!("chunks/5.csv"),
-    include_str!("chunks/6.csv"),
-    include_str!("chunks/7.csv"),
-    include_str!("chunks/8.csv"),
-    include_str!("chunks/9.csv"),
-    include_str!("chunks/10.csv"),
-    include_str!("chunks/11.csv"),
-    include_str!("chunks/12.csv"),
-    include_str!("chunks/13.csv"),
-    include_str!("chunks/14.csv"),
-    include_str!("chunks/15.csv"),
-    include_str!("chunks/16.csv"),
-];
+// every export in `chunks/` is bundled, ordered by the number in its file name. The list is generated by `build.rs`,
+// so a newer ARK export can be added by dropping it into the directory as the next numbered csv
+include!(concat!(env!("OUT_DIR"), "/intel_chunks.rs"));
 
 pub fn get_intel_cpus() -> Vec<Cpu<&'static str>> {
     let mut merged_vec: Vec<Cpu<&str>> = Vec::with_capacity(1024);
@@ -77,6 +61,6 @@ mod tests {
 
     #[test]
     fn it_work() {
-        parse_csv(CHUNKS[15]).unwrap();
+        parse_csv(CHUNKS[CHUNKS.len() - 1]).unwrap();
     }
 }
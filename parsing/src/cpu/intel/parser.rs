@@ -1,5 +1,5 @@
 use super::lexer::lex_csv;
-use crate::cpu::Cpu;
+use crate::cpu::{AttributeSection, Cpu};
 use std::collections::HashMap;
 use std::error::Error;
 
@@ -11,14 +11,29 @@ pub fn parse_csv(csv: &'_ str) -> Result<Vec<Cpu<&str>>, Box<dyn Error + '_>> {
         let mut cpu: Cpu<&str> = Cpu {
             name: cpu_name,
             attributes: HashMap::new(),
+            sections: Vec::new(),
         };
-        for record in lexer_output.records.clone() {
-            // println!("{:#?}", record[0].to_string());
-            // the first item in each record is the label, the items are in columns with the cpus
-            // println!("{:#?}: {:#?}, {:#?}", record[0].to_string(), record[1].to_string(), record[2].to_string());
-            let entry = record[i + 1];
-            if !entry.is_empty() {
-                cpu.attributes.insert(record[0], entry);
+        for section in &lexer_output.sections {
+            let mut attributes: Vec<(&str, &str)> = Vec::new();
+            // the first item in each record is the label, the items are in columns with the cpus.
+            // Rows that don't have a column for every cpu aren't attributes, like a stray header or timestamp
+            for record in section
+                .records
+                .iter()
+                .filter(|r| r.len() > lexer_output.cpus.len())
+            {
+                let label = normalize_label(record[0]);
+                let entry = record[i + 1];
+                if !label.is_empty() && !entry.is_empty() {
+                    cpu.attributes.insert(label, entry);
+                    attributes.push((label, entry));
+                }
+            }
+            if !attributes.is_empty() {
+                cpu.sections.push(AttributeSection {
+                    name: normalize_label(section.name),
+                    attributes,
+                });
             }
         }
         output.push(cpu);
@@ -26,3 +41,38 @@ pub fn parse_csv(csv: &'_ str) -> Result<Vec<Cpu<&str>>, Box<dyn Error + '_>> {
 
     Ok(output)
 }
+
+/// ARK marks some labels with footnotes (`‡` or `†`), sometimes padded with extra whitespace, like
+/// "ECC Memory Supported   ‡". Those are stripped so labels can be compared directly
+fn normalize_label(label: &str) -> &str {
+    label.trim_matches(|c: char| c == '‡' || c == '†' || c.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_csv;
+
+    #[test]
+    fn sections_and_labels() {
+        let mock_file = "\u{feff}ARK | Intel® Product Specification Comparison\n01/25/2024 11:22:20 AM\n ,cpu a ,cpu b \nEssentials\nProcessor Number,a1,b1\nLithography,,7 nm\n\nMemory Specifications\nECC Memory Supported   ‡,Yes,No\n01/25/2024 11:22:20 AM\n";
+        let cpus = parse_csv(mock_file).unwrap();
+        assert_eq!(cpus[0].name, "cpu a");
+        let sections: Vec<(&str, Vec<(&str, &str)>)> = cpus[0]
+            .sections
+            .iter()
+            .map(|s| (s.name, s.attributes.clone()))
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                ("Essentials", vec![("Processor Number", "a1")]),
+                (
+                    "Memory Specifications",
+                    vec![("ECC Memory Supported", "Yes")]
+                ),
+            ]
+        );
+        assert_eq!(cpus[1].sections[0].attributes[1], ("Lithography", "7 nm"));
+        assert_eq!(cpus[1].attributes.len(), 3);
+    }
+}
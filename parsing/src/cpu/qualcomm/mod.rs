@@ -0,0 +1,21 @@
+use super::amd::parse_csv;
+use super::Cpu;
+
+// input.csv was put together by hand from the product briefs at https://www.qualcomm.com/laptops/products, Qualcomm
+// doesn't offer an export. It's laid out like the AMD export, so the AMD parser is reused
+
+const INPUT_FILE: &str = include_str!("input.csv");
+
+pub fn get_qualcomm_cpus() -> Vec<Cpu<&'static str>> {
+    parse_csv(INPUT_FILE).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_csv, INPUT_FILE};
+
+    #[test]
+    fn it_work() {
+        parse_csv(INPUT_FILE).unwrap();
+    }
+}
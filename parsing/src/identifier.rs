@@ -0,0 +1,214 @@
+//! Device identifiers show up in a few different formats depending on the operating system they were collected on.
+//! This module figures out which format an identifier is in, so that the PCIe and USB parsers can read each one.
+use nom::bytes::complete::{tag, take_while_m_n};
+use nom::character::complete::char;
+use nom::combinator::{map, opt};
+use nom::error::ErrorKind;
+use nom::sequence::{terminated, tuple};
+use nom::IResult;
+use std::fmt;
+
+use crate::NomError;
+
+/// The formats a device identifier can be submitted in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdentifierFormat {
+    /// A Windows PnP hardware or compatible ID, like `PCI\VEN_8086&DEV_5916` or `USB\VID_1532&PID_0084`
+    Windows,
+    /// A Linux modalias string, like `pci:v00008086d00005916sv00001043sd00001A30bc03sc00i00`
+    /// or `usb:v1532p0084d0200dc00dsc00dp00ic03isc01ip02in00`
+    Modalias,
+    /// A line of `lspci -n` or `lspci -nn` output, like
+    /// `00:02.0 VGA compatible controller [0300]: Intel Corporation HD Graphics 620 [8086:5916] (rev 02)`
+    Lspci,
+    /// A line of `lsusb` output, like `Bus 001 Device 002: ID 1532:0084 Razer USA, Ltd DeathAdder V2`
+    Lsusb,
+}
+
+/// The reasons a device identifier lookup can fail. The first three mean the identifier itself is malformed,
+/// while the last means the identifier was read fine, but nothing in it is in the database
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IdentifierError {
+    /// The identifier isn't in a recognized format, contains the part of the identifier that couldn't be read
+    MalformedPrefix(String),
+    /// A field that should be hex contains something else, contains the field
+    NonHexDigit(String),
+    /// The identifier ends partway through a field, contains the part of the identifier that was cut short
+    TruncatedField(String),
+    /// The vendor id isn't in the database, and there was no class to fall back on
+    UnknownVendor(u16),
+}
+
+impl IdentifierError {
+    /// Whether the error was caused by malformed input, rather than a lookup that came up empty
+    pub fn is_malformed(&self) -> bool {
+        matches!(
+            self,
+            Self::MalformedPrefix(_) | Self::NonHexDigit(_) | Self::TruncatedField(_)
+        )
+    }
+
+    /// A short, stable name for the error, intended for machine consumption
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::MalformedPrefix(_) => "malformed_prefix",
+            Self::NonHexDigit(_) => "non_hex_digit",
+            Self::TruncatedField(_) => "truncated_field",
+            Self::UnknownVendor(_) => "unknown_vendor",
+        }
+    }
+}
+
+impl fmt::Display for IdentifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedPrefix(rest) => write!(f, "unrecognized identifier format at {rest:?}"),
+            Self::NonHexDigit(field) => write!(f, "expected a hex number, found {field:?}"),
+            Self::TruncatedField(rest) => {
+                write!(f, "the identifier ends partway through a field at {rest:?}")
+            }
+            Self::UnknownVendor(vendor_id) => {
+                write!(f, "no vendor was found with the id {vendor_id:04x}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IdentifierError {}
+
+impl From<NomError<'_>> for IdentifierError {
+    /// Work out what went wrong from where and how the parser failed
+    fn from(error: NomError<'_>) -> Self {
+        let error = match error {
+            nom::Err::Incomplete(_) => return Self::TruncatedField(String::new()),
+            nom::Err::Error(e) | nom::Err::Failure(e) => e,
+        };
+        let input = error.input;
+        match error.code {
+            // a fixed width hex field was either cut short, or contains something that isn't hex
+            ErrorKind::TakeWhileMN => {
+                let after_digits = input.trim_start_matches(|c: char| c.is_ascii_hexdigit());
+                if after_digits.starts_with(|c: char| c.is_alphanumeric()) {
+                    let field_end = input
+                        .find(|c: char| !c.is_alphanumeric())
+                        .unwrap_or(input.len());
+                    Self::NonHexDigit(input[..field_end].to_string())
+                } else {
+                    Self::TruncatedField(input.to_string())
+                }
+            }
+            _ if input.is_empty() => Self::TruncatedField(input.to_string()),
+            _ => Self::MalformedPrefix(input.to_string()),
+        }
+    }
+}
+
+/// Guess the format of a device identifier. Anything that isn't recognized as a Linux format is
+/// assumed to be a Windows identifier
+pub fn detect_format(input: &str) -> IdentifierFormat {
+    let input = input.trim_start();
+    if input.starts_with("pci:") || input.starts_with("usb:") {
+        IdentifierFormat::Modalias
+    } else if input.starts_with("Bus ") && input.contains(" ID ") {
+        IdentifierFormat::Lsusb
+    } else if read_pci_slot(input).is_ok() {
+        IdentifierFormat::Lspci
+    } else {
+        IdentifierFormat::Windows
+    }
+}
+
+/// Read the PCI slot at the start of an `lspci` line, in the form of `[domain:]bus:device.function`, followed by a space
+pub(crate) fn read_pci_slot(input: &str) -> IResult<&str, &str> {
+    let (remainder, _) = tuple((
+        opt(terminated(hex_digits(4), char(':'))),
+        terminated(hex_digits(2), char(':')),
+        terminated(hex_digits(2), char('.')),
+        terminated(take_while_m_n(1, 1, |c: char| c.is_digit(8)), tag(" ")),
+    ))(input)?;
+    Ok((remainder, &input[..input.len() - remainder.len() - 1]))
+}
+
+/// Read exactly `digits` hex digits, `digits` should not be more than 4
+pub(crate) fn hex_digits<'a>(digits: usize) -> impl FnMut(&'a str) -> IResult<&'a str, u16> {
+    map(
+        take_while_m_n(digits, digits, |c: char| c.is_ascii_hexdigit()),
+        // the combinator above guarantees this is valid hex that fits
+        |hex| u16::from_str_radix(hex, 16).unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_format, hex_digits, read_pci_slot, IdentifierError, IdentifierFormat};
+
+    #[test]
+    fn basic_detect_format() {
+        assert_eq!(
+            detect_format("PCI\\VEN_8086&DEV_5916&SUBSYS_1A301043"),
+            IdentifierFormat::Windows
+        );
+        assert_eq!(
+            detect_format("USB\\VID_1532&PID_0084&MI_03"),
+            IdentifierFormat::Windows
+        );
+        assert_eq!(
+            detect_format("pci:v00008086d00005916sv00001043sd00001A30bc03sc00i00"),
+            IdentifierFormat::Modalias
+        );
+        assert_eq!(
+            detect_format("usb:v1532p0084d0200dc00dsc00dp00ic03isc01ip02in00"),
+            IdentifierFormat::Modalias
+        );
+        assert_eq!(
+            detect_format(
+                "00:02.0 VGA compatible controller [0300]: Intel Corporation HD Graphics 620 [8086:5916] (rev 02)"
+            ),
+            IdentifierFormat::Lspci
+        );
+        assert_eq!(
+            detect_format("0000:01:00.0 0300: 10de:1b80 (rev a1)"),
+            IdentifierFormat::Lspci
+        );
+        assert_eq!(
+            detect_format("Bus 001 Device 002: ID 1532:0084 Razer USA, Ltd DeathAdder V2"),
+            IdentifierFormat::Lsusb
+        );
+    }
+
+    #[test]
+    fn basic_read_pci_slot() {
+        assert_eq!(read_pci_slot("00:1f.3 0403"), Ok(("0403", "00:1f.3")));
+        assert_eq!(
+            read_pci_slot("0000:00:1f.3 0403"),
+            Ok(("0403", "0000:00:1f.3"))
+        );
+        assert!(read_pci_slot("00:1f 0403").is_err());
+    }
+
+    #[test]
+    fn classify_errors() {
+        let error = |input, digits| IdentifierError::from(hex_digits(digits)(input).unwrap_err());
+        assert_eq!(
+            error("ZZZZ&DEV_0000", 4),
+            IdentifierError::NonHexDigit(String::from("ZZZZ"))
+        );
+        assert_eq!(
+            error("C09", 4),
+            IdentifierError::TruncatedField(String::from("C09"))
+        );
+        assert_eq!(
+            error("C0&MI_00", 4),
+            IdentifierError::TruncatedField(String::from("C0&MI_00"))
+        );
+        assert!(IdentifierError::MalformedPrefix(String::new()).is_malformed());
+        assert!(!IdentifierError::UnknownVendor(0xffff).is_malformed());
+    }
+
+    #[test]
+    fn basic_hex_digits() {
+        assert_eq!(hex_digits(4)("1a30bc"), Ok(("bc", 0x1a30)));
+        assert!(hex_digits(4)("1a3").is_err());
+        assert!(hex_digits(2)("zz").is_err());
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod bugcheck;
 pub mod cpu;
+pub mod identifier;
 pub mod pcie;
 pub mod usb;
 
@@ -1,14 +1,35 @@
+use crate::identifier::{
+    detect_format, hex_digits, read_pci_slot, IdentifierError, IdentifierFormat,
+};
 use crate::NomError;
-use nom::bytes::complete::{tag, take, take_until};
+use nom::bytes::complete::{is_not, tag, take, take_until};
 use nom::character::complete::char;
-use nom::sequence::{delimited, preceded, terminated};
+use nom::combinator::opt;
+use nom::error::ErrorKind;
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
 use nom::IResult;
 // https://stackoverflow.com/a/70552843
 // this library is used for a very fast hashmap implementation because we're not worried about DOS attacks
 use nohash_hasher::BuildNoHashHasher;
 use std::collections::HashMap;
 
-pub type PcieDeviceInfo = (Option<Vendor>, Option<Device>, Option<Subsystem>);
+/// A tuple of (`vendor`, `device`, `subsystem`, `subsystem vendor name`, `class`). The subsystem vendor is the company that
+/// built the card or board (e.g. `1043` → ASUSTeK), and it's resolved independently of whether the subsystem itself is known.
+/// The class is only resolved if the identifier contains a `CC_` class code
+/// Only names are returned, and they're borrowed from the cache, so a lookup doesn't allocate
+pub type PcieDeviceInfo<'a> = (
+    Option<&'a str>,
+    Option<&'a str>,
+    Option<&'a str>,
+    Option<&'a str>,
+    Option<DeviceClass<'a>>,
+);
+
+/// The ids read out of a device identifier string, see [parse_device_identifier]
+pub type ParsedIdentifier = (u16, u16, Option<(u16, u16)>, Option<ClassCode>);
+
+/// A class code read from a `CC_ccss` or `CC_ccsspp` field, in the form of (`class`, `subclass`, `programming interface`)
+pub type ClassCode = (u8, u8, Option<u8>);
 
 // the input file was obtained from https://pci-ids.ucw.cz/
 const FILE_INPUT: &str = include_str!("./pci.ids.txt");
@@ -31,19 +52,58 @@ pub struct Device {
 }
 
 /// Subsystems are placed directly under the relevant [Device] in the tree,
-/// and are marked with two tabs before, the [Vendor] ID, a space, then the subsystem ID,
+/// and are marked with two tabs before, the subsystem [Vendor] ID, a space, then the subsystem ID,
 /// then two spaces, then the name of the subsystem
 #[derive(PartialEq, Debug, Clone)]
 pub struct Subsystem {
+    /// The ID of the vendor that built the subsystem, this is not always the same as the device vendor
+    pub vendor_id: u16,
     pub id: u16,
     pub name: String,
 }
 
+/// Classes are placed at the root of the last section of the file, after every vendor. They're marked with
+/// `C`, a space, the class ID, then two spaces and the class name
+#[derive(PartialEq, Debug, Clone)]
+pub struct Class {
+    pub id: u8,
+    pub name: String,
+    pub subclasses: HashMap<u8, Subclass, BuildNoHashHasher<u8>>,
+}
+
+/// Subclasses are placed directly under the relevant [Class] in the tree,
+/// and are marked with one tab before, the subclass ID, then two spaces and the subclass name
+#[derive(PartialEq, Debug, Clone)]
+pub struct Subclass {
+    pub id: u8,
+    pub name: String,
+    pub prog_ifs: HashMap<u8, ProgrammingInterface, BuildNoHashHasher<u8>>,
+}
+
+/// Programming interfaces are placed directly under the relevant [Subclass] in the tree,
+/// and are marked with two tabs before, the programming interface ID, then two spaces and the name
+#[derive(PartialEq, Debug, Clone)]
+pub struct ProgrammingInterface {
+    pub id: u8,
+    pub name: String,
+}
+
+/// The names resolved from a class code, like "Display controller", "VGA compatible controller", and "VGA controller".
+/// Fields are only populated as far as the class code and database allow
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct DeviceClass<'a> {
+    pub class: &'a str,
+    pub subclass: Option<&'a str>,
+    pub prog_if: Option<&'a str>,
+}
+
 /// An interface for fetching and storing pcie devices
 #[derive(Clone)]
 pub struct PcieCache {
     /// A list of vendors, where each vendor contains associated devices and subsystems
-    vendors: HashMap<u16, Vendor, BuildNoHashHasher<u16>>,
+    pub vendors: HashMap<u16, Vendor, BuildNoHashHasher<u16>>,
+    /// A list of device classes, where each class contains associated subclasses and programming interfaces
+    pub classes: HashMap<u8, Class, BuildNoHashHasher<u8>>,
 }
 
 impl PcieCache {
@@ -51,36 +111,70 @@ impl PcieCache {
     pub fn new() -> Self {
         let mut vendors: HashMap<u16, Vendor, BuildNoHashHasher<u16>> =
             HashMap::with_capacity_and_hasher(512, BuildNoHashHasher::default());
-        for vendor in parse_pcie_db().unwrap() {
+        let mut classes: HashMap<u8, Class, BuildNoHashHasher<u8>> =
+            HashMap::with_capacity_and_hasher(32, BuildNoHashHasher::default());
+        let (parsed_vendors, parsed_classes) = parse_pcie_db().unwrap();
+        for vendor in parsed_vendors {
             vendors.insert(vendor.id, vendor);
         }
+        for class in parsed_classes {
+            classes.insert(class.id, class);
+        }
         // cut down on those unnecessary allocations again (1gb vps life)
         vendors.shrink_to_fit();
-        Self { vendors }
+        classes.shrink_to_fit();
+        Self { vendors, classes }
     }
 
+    /// Look up a device identifier. Everything that can be resolved is returned, so a device that isn't in the database
+    /// still has its vendor and class named. An error is only returned if the identifier is malformed, or if neither the
+    /// vendor nor the class could be found
     #[tracing::instrument(name = "pcie_lookup", skip(self))]
-    pub fn find<'a>(&'a self, input: &'a str) -> Result<PcieDeviceInfo, NomError<'a>> {
+    pub fn find(&self, input: &str) -> Result<PcieDeviceInfo<'_>, IdentifierError> {
         let parsed_identifier = parse_device_identifier(input)?;
-        // search for a vendor
         let vendor = self.vendors.get(&parsed_identifier.0);
-
-        // if one were looking for even more performance, subsystem and device search should ideally also be done with a hashmap
-        let mut device: Option<&Device> = None;
-        if let Some(ven) = vendor {
-            // a lot of these memory allocations are entirely necessary, just return a reference and figure out lifetimes
-            device = ven.devices.get(&parsed_identifier.1);
+        let device = vendor.and_then(|v| v.devices.get(&parsed_identifier.1));
+        let class = parsed_identifier
+            .3
+            .and_then(|class_code| self.find_class(class_code));
+        if vendor.is_none() && class.is_none() {
+            return Err(IdentifierError::UnknownVendor(parsed_identifier.0));
         }
 
-        let mut subsystem: Option<Subsystem> = None;
-        if let Some(dev) = device {
-            subsystem = dev
-                .subsystems
-                .iter()
-                .find(|s| s.id == parsed_identifier.1)
-                .cloned();
+        let mut subsystem: Option<&str> = None;
+        let mut subsystem_vendor: Option<&str> = None;
+        if let Some((ss_vid, ss_id)) = parsed_identifier.2 {
+            // devices rarely have more than a handful of subsystems, so a linear search is fine here
+            subsystem = device.and_then(|d| {
+                d.subsystems
+                    .iter()
+                    .find(|s| s.vendor_id == ss_vid && s.id == ss_id)
+                    .map(|s| s.name.as_str())
+            });
+            subsystem_vendor = self.vendors.get(&ss_vid).map(|v| v.name.as_str());
         }
-        Ok((vendor.cloned(), device.cloned(), subsystem))
+        Ok((
+            vendor.map(|v| v.name.as_str()),
+            device.map(|d| d.name.as_str()),
+            subsystem,
+            subsystem_vendor,
+            class,
+        ))
+    }
+
+    /// Resolve a class code into the names of the class, subclass and programming interface
+    fn find_class(&self, class_code: ClassCode) -> Option<DeviceClass<'_>> {
+        let (class_id, subclass_id, prog_if_id) = class_code;
+        let class = self.classes.get(&class_id)?;
+        let subclass = class.subclasses.get(&subclass_id);
+        let prog_if = subclass
+            .zip(prog_if_id)
+            .and_then(|(sc, id)| sc.prog_ifs.get(&id));
+        Some(DeviceClass {
+            class: &class.name,
+            subclass: subclass.map(|sc| sc.name.as_str()),
+            prog_if: prog_if.map(|pi| pi.name.as_str()),
+        })
     }
 }
 
@@ -90,42 +184,151 @@ impl Default for PcieCache {
     }
 }
 
-/// This function searches the input string for a vendor id, a product id, and optionally a subsystem ID
-/// input strings are expected in the format of:
+/// This function searches the input string for a vendor id, a product id, and optionally a subsystem ID and class code.
+/// The format of the input is detected with [detect_format], and Windows identifiers, modalias strings and `lspci -n`/`lspci -nn`
+/// lines are accepted.
+///
+/// Output is returned as a tuple of (`vendor`, `device`, `subsystem`, `class`), where `subsystem` is a tuple of
+/// (`subsystem vendor`, `subsystem device`)
+pub fn parse_device_identifier(input: &str) -> Result<ParsedIdentifier, IdentifierError> {
+    let input = input.trim();
+    let parsed = match detect_format(input) {
+        IdentifierFormat::Windows => parse_windows_identifier(input),
+        IdentifierFormat::Modalias => parse_modalias(input),
+        IdentifierFormat::Lspci => parse_lspci_line(input),
+        IdentifierFormat::Lsusb => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            ErrorKind::Tag,
+        ))),
+    };
+    Ok(parsed?)
+}
+
+/// Read a Windows PnP identifier. Input strings are expected in the format of:
 ///
 /// `PCI\VEN_10EC&DEV_8168&SUBSYS_86771043&REV_15\6&102E3ADF&0&0048020A`
 ///
-/// Output is returned as a tuple of (`vendor`, `device`, `subsystem`)
-fn parse_device_identifier(input: &str) -> Result<(u16, u16, Option<u16>), NomError<'_>> {
-    // TODO: validate that ids are hex strings
-    let vid_combinator = delimited(tag("PCI\\VEN_"), take(4_u8), char('&'))(input)?;
+/// or
+///
+/// `PCI\VEN_8086&DEV_A123&CC_0C0500`
+///
+/// Note that the identifier stores the subsystem as `SUBSYS_DDDDVVVV`, with the subsystem device ID *before* the subsystem vendor ID
+fn parse_windows_identifier(input: &str) -> Result<ParsedIdentifier, NomError<'_>> {
     // https://learn.microsoft.com/en-us/windows-hardware/drivers/install/identifiers-for-pci-devices
-    let did_combinator = preceded(tag("DEV_"), take(4_u8))(vid_combinator.0)?;
-    let mut ssid: Option<&str> = None;
-    if did_combinator.0.starts_with("&SU") {
-        let ssid_combinator = preceded(tag("&SUBSYS_"), take(4_u8))(did_combinator.0)?;
-        ssid = Some(ssid_combinator.1);
+    let (rest, vid) = preceded(tag("PCI\\VEN_"), hex_digits(4))(input)?;
+    let (rest, did) = preceded(tag("&DEV_"), hex_digits(4))(rest)?;
+    let mut subsys: Option<(u16, u16)> = None;
+    if rest.starts_with("&SUBSYS_") {
+        let (_, (ss_did, ss_vid)) =
+            preceded(tag("&SUBSYS_"), pair(hex_digits(4), hex_digits(4)))(rest)?;
+        subsys = Some((ss_vid, ss_did));
     }
+    // the class code can show up after the device or revision, so just search for it
+    let mut class_code: Option<ClassCode> = None;
+    if let Ok((cc, _)) = preceded(
+        take_until::<_, _, nom::error::Error<_>>("&CC_"),
+        tag("&CC_"),
+    )(rest)
+    {
+        // the programming interface is optional
+        let (_, (class, subclass, prog_if)) =
+            tuple((hex_digits(2), hex_digits(2), opt(hex_digits(2))))(cc)?;
+        class_code = Some((class as u8, subclass as u8, prog_if.map(|p| p as u8)));
+    }
+
+    Ok((vid, did, subsys, class_code))
+}
 
+/// Read a PCI modalias string, in the format of:
+///
+/// `pci:v00008086d00005916sv00001043sd00001A30bc03sc00i00`
+///
+/// Every id is padded out to 8 digits, but only the last 4 are ever used
+fn parse_modalias(input: &str) -> Result<ParsedIdentifier, NomError<'_>> {
+    // https://www.kernel.org/doc/html/latest/PCI/pci.html
+    let padded_id = |key| preceded(tuple((tag(key), tag("0000"))), hex_digits(4));
+    let (_, (vid, did, ss_vid, ss_did, class, subclass, prog_if)) = tuple((
+        padded_id("pci:v"),
+        padded_id("d"),
+        padded_id("sv"),
+        padded_id("sd"),
+        preceded(tag("bc"), hex_digits(2)),
+        preceded(tag("sc"), hex_digits(2)),
+        preceded(tag("i"), hex_digits(2)),
+    ))(input)?;
     Ok((
-        u16::from_str_radix(vid_combinator.1, 16).unwrap(),
-        u16::from_str_radix(did_combinator.1, 16).unwrap(),
-        ssid.map(|s| u16::from_str_radix(s, 16).unwrap()),
+        vid,
+        did,
+        Some((ss_vid, ss_did)),
+        Some((class as u8, subclass as u8, Some(prog_if as u8))),
     ))
 }
 
-/// Read the database from the file into memory
-fn parse_pcie_db() -> Result<Vec<Vendor>, NomError<'static>> {
+/// Read a line of `lspci -nn` or `lspci -n` output, in the format of:
+///
+/// `00:14.0 USB controller [0c03]: Intel Corporation Sunrise Point-LP USB 3.0 xHCI Controller [8086:9d2f] (rev 21) (prog-if 30 [XHCI])`
+///
+/// or
+///
+/// `00:14.0 0c03: 8086:9d2f (rev 21) (prog-if 30 [XHCI])`
+///
+/// `lspci` doesn't include the subsystem on the same line as the device, so it's never returned
+fn parse_lspci_line(input: &str) -> Result<ParsedIdentifier, NomError<'_>> {
+    let (rest, _) = read_pci_slot(input)?;
+    let vendor_and_device = || separated_pair(hex_digits(4), char(':'), hex_digits(4));
+    let ((class, subclass), (vid, did)) = match terminated(hex_digits(4), tag(": "))(rest) {
+        // `lspci -n`, the ids directly follow the class
+        Ok((rest, class_code)) => (
+            class_code.to_be_bytes().into(),
+            vendor_and_device()(rest)?.1,
+        ),
+        // `lspci -nn`, every id is in brackets after a name, and the name of the device may contain brackets of its own
+        Err(_) => {
+            let (rest, class_code) =
+                preceded(is_not("["), delimited(char('['), hex_digits(4), tag("]: ")))(rest)?;
+            let ids = rest
+                .match_indices('[')
+                .find_map(|(i, _)| {
+                    delimited(char('['), vendor_and_device(), char(']'))(&rest[i..]).ok()
+                })
+                .ok_or(nom::Err::Error(nom::error::Error::new(
+                    rest,
+                    ErrorKind::Tag,
+                )))?;
+            (class_code.to_be_bytes().into(), ids.1)
+        }
+    };
+    // the programming interface is only shown if it's not zero
+    let prog_if = input
+        .find("(prog-if ")
+        .and_then(|i| hex_digits(2)(&input[i + "(prog-if ".len()..]).ok())
+        .map(|(_, prog_if)| prog_if as u8);
+    Ok((vid, did, None, Some((class, subclass, prog_if))))
+}
+
+/// Read the database from the file into memory, returning a list of vendors and a list of device classes
+fn parse_pcie_db() -> Result<(Vec<Vendor>, Vec<Class>), NomError<'static>> {
     let header_combinator = read_header(FILE_INPUT)?;
     // this is filled up as the db is parsed
     let mut output: Vec<Vendor> = Vec::with_capacity(512);
-    let mut iterated_output = read_vendor(header_combinator.0);
+    let mut leftover = header_combinator.0;
+    let mut iterated_output = read_vendor(leftover);
     while let Ok(ref section_output) = iterated_output {
         output.push(section_output.1.clone());
+        leftover = section_output.0;
         iterated_output = read_vendor(section_output.0);
     }
 
-    Ok(output)
+    // the class list comes after the vendor list
+    let class_header_combinator = read_class_header(leftover)?;
+    let mut classes: Vec<Class> = Vec::with_capacity(32);
+    let mut iterated_output = read_class(class_header_combinator.0);
+    while let Ok(section_output) = iterated_output {
+        classes.push(section_output.1);
+        iterated_output = read_class(section_output.0);
+    }
+
+    Ok((output, classes))
 }
 
 // read the commented header of the input up until the start of the actual list
@@ -133,6 +336,11 @@ fn read_header(input: &str) -> IResult<&str, &str> {
     take_until("0001 ")(input)
 }
 
+// read the commented header of the class list up until the start of the actual list
+fn read_class_header(input: &str) -> IResult<&str, &str> {
+    take_until("C 00  ")(input)
+}
+
 // read a single vendor block and all associated devices/subsystems from the input
 fn read_vendor(input: &str) -> IResult<&str, Vendor> {
     let vid_combinator = terminated(take(4_u8), tag("  "))(input)?;
@@ -212,7 +420,7 @@ fn read_subsystem_line(input: &str) -> IResult<&str, Subsystem> {
     // subsystems in the file are identified by two tabs, the vendor ID, a space, the subsystem id,
     // two spaces, then the name.
 
-    // the vid is not needed, but might as well break the parsing down in steps
+    // the subsystem vendor id
     let vid_combinator = delimited(tag("\t\t"), take(4_u8), char(' '))(input)?;
 
     // subsystem id
@@ -221,12 +429,97 @@ fn read_subsystem_line(input: &str) -> IResult<&str, Subsystem> {
     Ok((
         ss_name_combinator.0,
         Subsystem {
+            vendor_id: u16::from_str_radix(vid_combinator.1, 16).unwrap(),
             id: u16::from_str_radix(ssid_combinator.1, 16).unwrap(),
             name: String::from(ss_name_combinator.1),
         },
     ))
 }
 
+// read a single class block and all associated subclasses/programming interfaces from the input
+fn read_class(input: &str) -> IResult<&str, Class> {
+    let cid_combinator = delimited(tag("C "), take(2_u8), tag("  "))(input)?;
+    let cname_combinator = terminated(take_until("\n"), char('\n'))(cid_combinator.0)?;
+    // read until the next line doesn't start with a tab
+    let mut subclasses: HashMap<u8, Subclass, BuildNoHashHasher<u8>> =
+        HashMap::with_hasher(BuildNoHashHasher::default());
+    let mut iterated_output = read_subclass(cname_combinator.0);
+    let mut leftover = cname_combinator.0;
+    loop {
+        if let Ok(combinator_output) = iterated_output {
+            leftover = combinator_output.0;
+            subclasses.insert(combinator_output.1.id, combinator_output.1);
+            iterated_output = read_subclass(combinator_output.0);
+        } else {
+            // Some lines have comments, handle those here, this is assuming the next line is indented
+            if leftover.starts_with('#') {
+                leftover = preceded(take_until("\n"), char('\n'))(leftover)?.0;
+                iterated_output = read_subclass(leftover);
+                continue;
+            }
+            break;
+        }
+    }
+
+    Ok((
+        leftover,
+        Class {
+            id: u8::from_str_radix(cid_combinator.1, 16).unwrap(),
+            name: String::from(cname_combinator.1),
+            subclasses,
+        },
+    ))
+}
+
+// read a single subclass and all associated programming interfaces (if applicable) from the input
+fn read_subclass(input: &str) -> IResult<&str, Subclass> {
+    let scid_combinator = preceded(char('\t'), take(2_u8))(input)?;
+    let scname_combinator = delimited(tag("  "), take_until("\n"), char('\n'))(scid_combinator.0)?;
+
+    // read until the next line doesn't start with two tabs
+    let mut prog_ifs: HashMap<u8, ProgrammingInterface, BuildNoHashHasher<u8>> =
+        HashMap::with_hasher(BuildNoHashHasher::default());
+    let mut iterated_output = read_prog_if_line(scname_combinator.0);
+    let mut leftover = scname_combinator.0;
+    loop {
+        if let Ok(combinator_output) = iterated_output {
+            leftover = combinator_output.0;
+            prog_ifs.insert(combinator_output.1.id, combinator_output.1);
+            iterated_output = read_prog_if_line(combinator_output.0);
+        } else {
+            // Some lines have comments, handle those here, this is assuming the next line is indented
+            if leftover.starts_with('#') {
+                leftover = preceded(take_until("\n"), char('\n'))(leftover)?.0;
+                iterated_output = read_prog_if_line(leftover);
+                continue;
+            }
+            break;
+        }
+    }
+
+    Ok((
+        leftover,
+        Subclass {
+            id: u8::from_str_radix(scid_combinator.1, 16).unwrap(),
+            name: String::from(scname_combinator.1),
+            prog_ifs,
+        },
+    ))
+}
+
+// read a single programming interface from the input
+fn read_prog_if_line(input: &str) -> IResult<&str, ProgrammingInterface> {
+    let pid_combinator = delimited(tag("\t\t"), take(2_u8), tag("  "))(input)?;
+    let pname_combinator = terminated(take_until("\n"), char('\n'))(pid_combinator.0)?;
+    Ok((
+        pname_combinator.0,
+        ProgrammingInterface {
+            id: u8::from_str_radix(pid_combinator.1, 16).unwrap(),
+            name: String::from(pname_combinator.1),
+        },
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -234,11 +527,13 @@ mod tests {
     use nohash_hasher::BuildNoHashHasher;
 
     use crate::pcie::{
-        parse_device_identifier, read_device, read_subsystem_line, read_vendor, Device, Subsystem,
-        Vendor,
+        parse_device_identifier, read_class, read_device, read_prog_if_line, read_subclass,
+        read_subsystem_line, read_vendor, Class, Device, DeviceClass, ProgrammingInterface,
+        Subclass, Subsystem, Vendor,
     };
 
-    use super::{parse_pcie_db, read_header, PcieCache};
+    use super::{parse_pcie_db, read_class_header, read_header, PcieCache};
+    use crate::identifier::IdentifierError;
 
     #[test]
     fn basic_read_header() {
@@ -257,6 +552,7 @@ mod tests {
             Ok((
                 "bat",
                 Subsystem {
+                    vendor_id: 0xabcd,
                     id: 0x0001,
                     name: String::from("foo bar")
                 }
@@ -288,6 +584,7 @@ mod tests {
                     id: 0x0001,
                     name: String::from("foo bar"),
                     subsystems: vec![Subsystem {
+                        vendor_id: 0x000a,
                         id: 0x8008,
                         name: String::from("subsys")
                     }],
@@ -304,6 +601,7 @@ mod tests {
                     id: 0x0001,
                     name: String::from("foo bar"),
                     subsystems: vec![Subsystem {
+                        vendor_id: 0x000a,
                         id: 0x8008,
                         name: String::from("subsys")
                     }],
@@ -338,10 +636,93 @@ mod tests {
         )
     }
 
+    #[test]
+    fn basic_read_class_header() {
+        let mock_header =
+            "ffff  Illegal Vendor ID\n\n# C class\tclass_name\n\nC 00  Unclassified device";
+        assert_eq!(
+            read_class_header(mock_header),
+            Ok((
+                "C 00  Unclassified device",
+                "ffff  Illegal Vendor ID\n\n# C class\tclass_name\n\n"
+            ))
+        )
+    }
+
+    #[test]
+    fn basic_read_prog_if_line() {
+        let mock_prog_if_line = "\t\t02  NVM Express\nbat";
+        assert_eq!(
+            read_prog_if_line(mock_prog_if_line),
+            Ok((
+                "bat",
+                ProgrammingInterface {
+                    id: 0x02,
+                    name: String::from("NVM Express")
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn basic_read_subclass() {
+        let mock_subclass =
+            "\t08  Non-Volatile memory controller\n# foo\n\t\t02  NVM Express\n\t09";
+        let mut mock_prog_ifs: HashMap<u8, ProgrammingInterface, BuildNoHashHasher<u8>> =
+            HashMap::with_hasher(BuildNoHashHasher::default());
+        mock_prog_ifs.insert(
+            0x02,
+            ProgrammingInterface {
+                id: 0x02,
+                name: String::from("NVM Express"),
+            },
+        );
+        assert_eq!(
+            read_subclass(mock_subclass),
+            Ok((
+                "\t09",
+                Subclass {
+                    id: 0x08,
+                    name: String::from("Non-Volatile memory controller"),
+                    prog_ifs: mock_prog_ifs
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn basic_read_class() {
+        let mock_class = "C 03  Display controller\n\t02  3D controller\nC 04";
+        let mut mock_subclasses: HashMap<u8, Subclass, BuildNoHashHasher<u8>> =
+            HashMap::with_hasher(BuildNoHashHasher::default());
+        mock_subclasses.insert(
+            0x02,
+            Subclass {
+                id: 0x02,
+                name: String::from("3D controller"),
+                prog_ifs: HashMap::with_hasher(BuildNoHashHasher::default()),
+            },
+        );
+        assert_eq!(
+            read_class(mock_class),
+            Ok((
+                "C 04",
+                Class {
+                    id: 0x03,
+                    name: String::from("Display controller"),
+                    subclasses: mock_subclasses
+                }
+            ))
+        );
+    }
+
     #[test]
     fn basic_parse_db() {
         // basically make sure a panic doesn't occur during parsing
-        parse_pcie_db().unwrap();
+        let (_, classes) = parse_pcie_db().unwrap();
+        // make sure the class list was read to the end of the file
+        assert_eq!(classes.first().map(|c| c.id), Some(0x00));
+        assert_eq!(classes.last().map(|c| c.id), Some(0xff));
     }
 
     #[test]
@@ -349,27 +730,69 @@ mod tests {
         // https://learn.microsoft.com/en-us/windows-hardware/drivers/install/identifiers-for-pci-devices
         assert_eq!(
             parse_device_identifier("PCI\\VEN_1234&DEV_5678&SUBSYS_91230000&REV_00"),
-            Ok((0x1234, 0x5678, Some(0x9123)))
+            Ok((0x1234, 0x5678, Some((0x0000, 0x9123)), None))
         );
         assert_eq!(
             parse_device_identifier("PCI\\VEN_1234&DEV_5678&SUBSYS_91230000"),
-            Ok((0x1234, 0x5678, Some(0x9123)))
+            Ok((0x1234, 0x5678, Some((0x0000, 0x9123)), None))
+        );
+        assert_eq!(
+            parse_device_identifier("PCI\\VEN_1234&DEV_5678&SUBSYS_86771043&REV_15"),
+            Ok((0x1234, 0x5678, Some((0x1043, 0x8677)), None))
         );
         assert_eq!(
             parse_device_identifier("PCI\\VEN_1234&DEV_5678&REV_00"),
-            Ok((0x1234, 0x5678, None))
+            Ok((0x1234, 0x5678, None, None))
         );
         assert_eq!(
             parse_device_identifier("PCI\\VEN_1234&DEV_5678"),
-            Ok((0x1234, 0x5678, None))
+            Ok((0x1234, 0x5678, None, None))
         );
         assert_eq!(
             parse_device_identifier("PCI\\VEN_1234&DEV_5678&CC_112200"),
-            Ok((0x1234, 0x5678, None))
+            Ok((0x1234, 0x5678, None, Some((0x11, 0x22, Some(0x00)))))
         );
         assert_eq!(
             parse_device_identifier("PCI\\VEN_1234&DEV_5678&CC_1122"),
-            Ok((0x1234, 0x5678, None))
+            Ok((0x1234, 0x5678, None, Some((0x11, 0x22, None))))
+        );
+        assert_eq!(
+            parse_device_identifier("PCI\\VEN_1234&DEV_5678&REV_01&CC_0C0500\\3&11583659&0&FC"),
+            Ok((0x1234, 0x5678, None, Some((0x0c, 0x05, Some(0x00)))))
+        );
+    }
+
+    #[test]
+    fn parse_linux_identifiers() {
+        assert_eq!(
+            parse_device_identifier("pci:v000010ECd00008168sv00001043sd00008505bc02sc00i00"),
+            Ok((
+                0x10ec,
+                0x8168,
+                Some((0x1043, 0x8505)),
+                Some((0x02, 0x00, Some(0x00)))
+            ))
+        );
+        assert_eq!(
+            parse_device_identifier(
+                "00:14.0 USB controller [0c03]: Intel Corporation Sunrise Point-LP USB 3.0 xHCI Controller [8086:9d2f] (rev 21) (prog-if 30 [XHCI])"
+            ),
+            Ok((0x8086, 0x9d2f, None, Some((0x0c, 0x03, Some(0x30)))))
+        );
+        // the device name contains brackets that aren't ids
+        assert_eq!(
+            parse_device_identifier(
+                "0000:01:00.0 VGA compatible controller [0300]: NVIDIA Corporation GP104 [GeForce GTX 1080] [10de:1b80] (rev a1)"
+            ),
+            Ok((0x10de, 0x1b80, None, Some((0x03, 0x00, None))))
+        );
+        assert_eq!(
+            parse_device_identifier("00:14.0 0c03: 8086:9d2f (rev 21) (prog-if 30)"),
+            Ok((0x8086, 0x9d2f, None, Some((0x0c, 0x03, Some(0x30)))))
+        );
+        assert!(parse_device_identifier("pci:v00008086d0000ZZZZ").is_err());
+        assert!(
+            parse_device_identifier("Bus 001 Device 002: ID 1532:0084 Razer USA, Ltd").is_err()
         );
     }
 
@@ -381,11 +804,98 @@ mod tests {
                 .find("PCI\\VEN_1022&DEV_1633&SUBSYS_14531022&REV_00\\3&2411E6FE&1&09")
                 .map(|t| t.1)
                 .unwrap(),
-            Some(Device {
-                id: 5683,
-                name: String::from("Renoir PCIe GPP Bridge"),
-                subsystems: vec![],
-            },),
+            Some("Renoir PCIe GPP Bridge"),
+        );
+    }
+
+    #[test]
+    fn find_errors() {
+        let cache = PcieCache::new();
+        assert_eq!(
+            cache.find("PCI\\VEN_ZZZZ&DEV_0000"),
+            Err(IdentifierError::NonHexDigit(String::from("ZZZZ")))
+        );
+        assert_eq!(
+            cache.find("PCI\\VEN_8086&DEV_59"),
+            Err(IdentifierError::TruncatedField(String::from("59")))
+        );
+        assert_eq!(
+            cache.find("PCI\\VEN_8086"),
+            Err(IdentifierError::TruncatedField(String::new()))
         );
+        assert_eq!(
+            cache.find("ACPI\\PNP0A08"),
+            Err(IdentifierError::MalformedPrefix(String::from(
+                "ACPI\\PNP0A08"
+            )))
+        );
+        assert_eq!(
+            cache.find("PCI\\VEN_8086&DEV_5916&CC_03ZZ"),
+            Err(IdentifierError::NonHexDigit(String::from("ZZ")))
+        );
+        assert_eq!(
+            cache.find("PCI\\VEN_0002&DEV_0000"),
+            Err(IdentifierError::UnknownVendor(0x0002))
+        );
+    }
+
+    #[test]
+    fn find_subsystem() {
+        let cache = PcieCache::new();
+        let (vendor, device, subsystem, subsystem_vendor, _) = cache
+            .find("PCI\\VEN_10EC&DEV_8168&SUBSYS_85051043&REV_06\\4&2A1C5B4F&0&00E5")
+            .unwrap();
+        assert_eq!(vendor, Some("Realtek Semiconductor Co., Ltd."));
+        assert_eq!(
+            device,
+            Some("RTL8111/8168/8211/8411 PCI Express Gigabit Ethernet Controller")
+        );
+        assert_eq!(subsystem, Some("P8 series motherboard"));
+        assert_eq!(subsystem_vendor, Some("ASUSTeK Computer Inc."));
+
+        // the board vendor should still be named even if the subsystem itself isn't in the database
+        let (_, _, subsystem, subsystem_vendor, _) = cache
+            .find("PCI\\VEN_10EC&DEV_8168&SUBSYS_FFFF1043&REV_06")
+            .unwrap();
+        assert_eq!(subsystem, None);
+        assert_eq!(subsystem_vendor, Some("ASUSTeK Computer Inc."));
+        // a modalias string should resolve to the same subsystem as the windows identifier
+        let (_, _, subsystem, _, class) = cache
+            .find("pci:v000010ECd00008168sv00001043sd00008505bc02sc00i00")
+            .unwrap();
+        assert_eq!(subsystem, Some("P8 series motherboard"));
+        assert_eq!(class.unwrap().class, "Network controller");
+    }
+
+    #[test]
+    fn find_class() {
+        let cache = PcieCache::new();
+        // the device id is made up, but the class should still be resolved
+        let (vendor, device, _, _, class) = cache.find("PCI\\VEN_8086&DEV_FFFE&CC_030000").unwrap();
+        assert_eq!(vendor, Some("Intel Corporation"));
+        assert_eq!(device, None);
+        assert_eq!(
+            class,
+            Some(DeviceClass {
+                class: "Display controller",
+                subclass: Some("VGA compatible controller"),
+                prog_if: Some("VGA controller"),
+            })
+        );
+
+        let (_, _, _, _, class) = cache.find("PCI\\VEN_8086&DEV_A123&CC_0C05").unwrap();
+        assert_eq!(
+            class,
+            Some(DeviceClass {
+                class: "Serial bus controller",
+                subclass: Some("SMBus"),
+                prog_if: None,
+            })
+        );
+
+        // an unknown vendor doesn't stop the class from being named
+        let (vendor, _, _, _, class) = cache.find("PCI\\VEN_0002&DEV_0000&CC_030000").unwrap();
+        assert_eq!(vendor, None);
+        assert_eq!(class.unwrap().class, "Display controller");
     }
 }
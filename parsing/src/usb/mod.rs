@@ -1,24 +1,28 @@
 use std::collections::HashMap;
 
 use nom::bytes::complete::{tag, take, take_until};
-use nom::character::complete::char;
-use nom::sequence::{delimited, preceded};
+use nom::character::complete::{char, hex_digit1};
+use nom::combinator::{all_consuming, opt};
+use nom::error::ErrorKind;
+use nom::sequence::{delimited, preceded, separated_pair, terminated, tuple};
 use nom::IResult;
 // https://stackoverflow.com/a/70552843
 // this library is used for a very fast hashmap implementation because we're not worried about DOS attacks
+use crate::identifier::{detect_format, hex_digits, IdentifierError, IdentifierFormat};
 use crate::NomError;
 use nohash_hasher::BuildNoHashHasher;
 
 // The input file was obtained from http://www.linux-usb.org/
-// note: only vendors and devices are currently read from the file, there's extra crap at the bottom that might be useful
-// This file contains one or two invalid utf 8 characters, so it's parsed slightly differently
+// note: only vendors, devices, classes, audio terminal types, languages and the HID tables are currently read from the file,
+// there's extra crap at the bottom that might be useful
+// This file contains a few latin-1 characters that aren't valid utf 8, so it's decoded with [decode_input] before parsing
 const INPUT_FILE: &[u8] = include_bytes!("usb.ids.txt");
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Vendor {
     pub id: u16,
     pub name: String,
-    pub devices: Vec<Device>,
+    pub devices: HashMap<u16, Device, BuildNoHashHasher<u16>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -27,46 +31,281 @@ pub struct Device {
     pub name: String,
 }
 
+/// Classes are listed after the vendors, and are marked with `C`, a space, the class ID, then two spaces and the class name
+#[derive(Clone, Debug, PartialEq)]
+pub struct Class {
+    pub id: u8,
+    pub name: String,
+    pub subclasses: Vec<Subclass>,
+}
+
+/// Subclasses are placed directly under the relevant [Class], and are marked with one tab before
+#[derive(Clone, Debug, PartialEq)]
+pub struct Subclass {
+    pub id: u8,
+    pub name: String,
+    pub protocols: Vec<Protocol>,
+}
+
+/// Protocols are placed directly under the relevant [Subclass], and are marked with two tabs before
+#[derive(Clone, Debug, PartialEq)]
+pub struct Protocol {
+    pub id: u8,
+    pub name: String,
+}
+
+/// The names resolved from a class code, like "Human Interface Device", "Boot Interface Subclass", and "Keyboard".
+/// Fields are only populated as far as the class code and database allow
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviceClass<'a> {
+    pub class: &'a str,
+    pub subclass: Option<&'a str>,
+    pub protocol: Option<&'a str>,
+}
+
+/// HID usage pages are listed in the `HUT` section, and are marked with `HUT`, a space, the usage page ID,
+/// then two spaces and the usage page name
+#[derive(Clone, Debug, PartialEq)]
+pub struct UsagePage {
+    pub id: u16,
+    pub name: String,
+    pub usages: Vec<Usage>,
+}
+
+/// HID usages are placed directly under the relevant [UsagePage], and are marked with one tab before,
+/// the usage ID, then two spaces and the usage name
+#[derive(Clone, Debug, PartialEq)]
+pub struct Usage {
+    pub id: u16,
+    pub name: String,
+}
+
+/// Languages are listed in the `L` section, and are marked with `L`, a space, the primary language ID,
+/// then two spaces and the language name
+#[derive(Clone, Debug, PartialEq)]
+pub struct Language {
+    pub id: u16,
+    pub name: String,
+    pub dialects: Vec<Dialect>,
+}
+
+/// Dialects are placed directly under the relevant [Language], and are marked with one tab before,
+/// the dialect (sublanguage) ID, then two spaces and the dialect name
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dialect {
+    pub id: u8,
+    pub name: String,
+}
+
+/// Everything found when searching for a device identifier, borrowed from the cache and the identifier.
+/// Fields are only populated as far as the identifier and database allow
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct UsbDeviceInfo<'a> {
+    pub vendor: Option<&'a str>,
+    pub device: Option<&'a str>,
+    /// The enumerator the identifier was read from as it was written, like `USB`, `HID` or `BTHENUM`
+    pub enumerator: Option<&'a str>,
+    /// The device release number in binary coded decimal, read from the `REV_` field of the identifier
+    pub revision: Option<u16>,
+    /// The interface number of a composite device, read from the `MI_` field of the identifier
+    pub interface: Option<u8>,
+    /// The class is only resolved from compatible IDs like `USB\Class_03&SubClass_01&Prot_02`
+    pub class: Option<DeviceClass<'a>>,
+}
+
+/// The fields read out of a device identifier string, see [parse_device_identifier]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParsedIdentifier<'a> {
+    pub enumerator: Option<&'a str>,
+    /// A tuple of (`vendor id`, `product id`), the product id can be missing from an identifier that only names a vendor
+    pub ids: Option<(u16, Option<u16>)>,
+    pub revision: Option<u16>,
+    pub interface: Option<u8>,
+    pub class: Option<ClassCode>,
+}
+
+impl ParsedIdentifier<'_> {
+    /// Fill in any fields that are missing with the fields from `other`, so that a list of
+    /// hardware and compatible IDs for the same device can be combined
+    fn merge(&mut self, other: Self) {
+        self.enumerator = self.enumerator.or(other.enumerator);
+        // an id with a product id is preferred over one that only has a vendor id
+        self.ids = match (self.ids, other.ids) {
+            (Some((_, None)), Some((vid, Some(pid)))) => Some((vid, Some(pid))),
+            (ids, other_ids) => ids.or(other_ids),
+        };
+        self.revision = self.revision.or(other.revision);
+        self.interface = self.interface.or(other.interface);
+        self.class = self.class.or(other.class);
+    }
+}
+
+/// A class code read from a compatible ID, in the form of (`class`, `subclass`, `protocol`)
+pub type ClassCode = (u8, Option<u8>, Option<u8>);
+
 #[derive(Clone)]
 pub struct UsbCache {
-    vendors: HashMap<u16, Vendor, BuildNoHashHasher<u16>>,
+    pub vendors: HashMap<u16, Vendor, BuildNoHashHasher<u16>>,
+    pub classes: HashMap<u8, Class, BuildNoHashHasher<u8>>,
+    pub hid_usage_pages: HashMap<u16, UsagePage, BuildNoHashHasher<u16>>,
+    /// HID report descriptor item types (`R`), keyed by the item prefix with the size bits cleared
+    pub report_items: HashMap<u8, String, BuildNoHashHasher<u8>>,
+    /// Physical descriptor bias types (`BIAS`)
+    pub biases: HashMap<u8, String, BuildNoHashHasher<u8>>,
+    /// Languages (`L`), keyed by the primary language ID
+    pub languages: HashMap<u16, Language, BuildNoHashHasher<u16>>,
+    /// Audio class terminal types (`AT`)
+    pub audio_terminals: HashMap<u16, String, BuildNoHashHasher<u16>>,
 }
 
 impl UsbCache {
     pub fn new() -> Self {
         let mut vendors: HashMap<u16, Vendor, BuildNoHashHasher<u16>> =
             HashMap::with_capacity_and_hasher(1024, BuildNoHashHasher::default());
-        for vendor in parse_usb_db() {
+        let mut classes: HashMap<u8, Class, BuildNoHashHasher<u8>> =
+            HashMap::with_capacity_and_hasher(32, BuildNoHashHasher::default());
+        let mut hid_usage_pages: HashMap<u16, UsagePage, BuildNoHashHasher<u16>> =
+            HashMap::with_capacity_and_hasher(32, BuildNoHashHasher::default());
+        let mut report_items: HashMap<u8, String, BuildNoHashHasher<u8>> =
+            HashMap::with_capacity_and_hasher(32, BuildNoHashHasher::default());
+        let mut biases: HashMap<u8, String, BuildNoHashHasher<u8>> =
+            HashMap::with_capacity_and_hasher(8, BuildNoHashHasher::default());
+        let mut languages: HashMap<u16, Language, BuildNoHashHasher<u16>> =
+            HashMap::with_capacity_and_hasher(128, BuildNoHashHasher::default());
+        let mut audio_terminals: HashMap<u16, String, BuildNoHashHasher<u16>> =
+            HashMap::with_capacity_and_hasher(64, BuildNoHashHasher::default());
+        let file_as_str = decode_input(INPUT_FILE);
+        let (parsed_vendors, parsed_classes) = parse_usb_db(&file_as_str);
+        for vendor in parsed_vendors {
             vendors.insert(vendor.id, vendor);
         }
+        for class in parsed_classes {
+            classes.insert(class.id, class);
+        }
+        for usage_page in parse_usage_pages(&file_as_str) {
+            hid_usage_pages.insert(usage_page.id, usage_page);
+        }
+        for (id, name) in parse_keyed_section(&file_as_str, "R ") {
+            report_items.insert(u8::from_str_radix(id, 16).unwrap(), String::from(name));
+        }
+        for (id, name) in parse_keyed_section(&file_as_str, "BIAS ") {
+            biases.insert(u8::from_str_radix(id, 16).unwrap(), String::from(name));
+        }
+        for language in parse_languages(&file_as_str) {
+            languages.insert(language.id, language);
+        }
+        for (id, name) in parse_keyed_section(&file_as_str, "AT ") {
+            audio_terminals.insert(u16::from_str_radix(id, 16).unwrap(), String::from(name));
+        }
         vendors.shrink_to_fit();
-        Self { vendors }
+        classes.shrink_to_fit();
+        hid_usage_pages.shrink_to_fit();
+        report_items.shrink_to_fit();
+        biases.shrink_to_fit();
+        languages.shrink_to_fit();
+        audio_terminals.shrink_to_fit();
+        Self {
+            vendors,
+            classes,
+            hid_usage_pages,
+            report_items,
+            biases,
+            languages,
+            audio_terminals,
+        }
     }
 
-    /// Search the cache for the provided input string, returning the found device info, if it exists. If the `Option<Vendor>` is `None`,
-    /// you can assume that the device info will also be `None`.
-    ///
-    /// TODO: this function calls unwrap on a very fallible function, change function
-    /// to return a Result, you could then make it so that vendor and device aren't options
+    /// Look up a device identifier. Everything that can be resolved is returned, so a device that isn't in the database
+    /// still has its vendor and class named. Compatible IDs don't contain a vendor or device, so they're only resolved
+    /// to a class. An error is only returned if the identifier is malformed, or if it has a vendor id that isn't in the
+    /// database and no class to fall back on
     #[tracing::instrument(name = "usb_lookup", skip(self))]
-    pub fn find<'a>(
-        &'a self,
-        input: &'a str,
-    ) -> Result<(Option<Vendor>, Option<Device>), NomError<'a>> {
+    pub fn find<'a>(&'a self, input: &'a str) -> Result<UsbDeviceInfo<'a>, IdentifierError> {
         let parsed_identifier = parse_device_identifier(input)?;
-        // first search for a vendor
-        let matching_vendor = self.vendors.get(&parsed_identifier.0);
-
-        let mut device: Option<Device> = None;
-        if let Some(vendor) = matching_vendor {
-            device = vendor
-                .devices
-                .iter()
-                .find(|dev| dev.id == parsed_identifier.1)
-                .cloned();
+        let class = parsed_identifier
+            .class
+            .and_then(|class_code| self.find_class(class_code));
+        let mut vendor: Option<&str> = None;
+        let mut device: Option<&str> = None;
+        if let Some((vid, pid)) = parsed_identifier.ids {
+            match self.vendors.get(&vid) {
+                Some(found_vendor) => {
+                    vendor = Some(&found_vendor.name);
+                    device = pid
+                        .and_then(|pid| found_vendor.devices.get(&pid))
+                        .map(|d| d.name.as_str());
+                }
+                None if class.is_none() => return Err(IdentifierError::UnknownVendor(vid)),
+                None => {}
+            }
         }
 
-        Ok((matching_vendor.cloned(), device))
+        Ok(UsbDeviceInfo {
+            vendor,
+            device,
+            enumerator: parsed_identifier.enumerator,
+            revision: parsed_identifier.revision,
+            interface: parsed_identifier.interface,
+            class,
+        })
+    }
+
+    /// Search the HID usage tables for a usage page, and optionally a usage on that page. `None` is returned if the usage page
+    /// is unknown, while an unknown usage only leaves the second value of the tuple as `None`
+    #[tracing::instrument(name = "hid_usage_lookup", skip(self))]
+    pub fn find_hid_usage(
+        &self,
+        usage_page: u16,
+        usage: Option<u16>,
+    ) -> Option<(&UsagePage, Option<&Usage>)> {
+        let page = self.hid_usage_pages.get(&usage_page)?;
+        let usage = usage.and_then(|id| page.usages.iter().find(|u| u.id == id));
+        Some((page, usage))
+    }
+
+    /// Find the name of a HID report descriptor item (like "Usage Page" or "Collection") from the item prefix byte.
+    /// The two least significant bits of the prefix encode the size of the data that follows, so they're ignored
+    pub fn find_report_item(&self, item: u8) -> Option<&str> {
+        self.report_items
+            .get(&(item & 0b1111_1100))
+            .map(String::as_str)
+    }
+
+    /// Find the name of a physical descriptor bias, like "Right Hand"
+    pub fn find_bias(&self, bias: u8) -> Option<&str> {
+        self.biases.get(&bias).map(String::as_str)
+    }
+
+    /// Search for the language and dialect of a USB language ID, like those used by string descriptors (e.g. `0x0409`
+    /// is English, US). The lower 10 bits of the ID are the primary language, and the upper 6 bits are the dialect.
+    /// `None` is returned if the primary language is unknown
+    #[tracing::instrument(name = "usb_language_lookup", skip(self))]
+    pub fn find_language(&self, language_id: u16) -> Option<(&Language, Option<&Dialect>)> {
+        let language = self.languages.get(&(language_id & 0x03ff))?;
+        let dialect_id = (language_id >> 10) as u8;
+        let dialect = language.dialects.iter().find(|d| d.id == dialect_id);
+        Some((language, dialect))
+    }
+
+    /// Find the name of a USB audio class terminal type, like "Headphones" for `0x0302`
+    #[tracing::instrument(name = "usb_audio_terminal_lookup", skip(self))]
+    pub fn find_audio_terminal(&self, terminal_type: u16) -> Option<&str> {
+        self.audio_terminals.get(&terminal_type).map(String::as_str)
+    }
+
+    /// Resolve a class code into the names of the class, subclass and protocol
+    fn find_class(&self, class_code: ClassCode) -> Option<DeviceClass<'_>> {
+        let (class_id, subclass_id, protocol_id) = class_code;
+        let class = self.classes.get(&class_id)?;
+        let subclass = subclass_id.and_then(|id| class.subclasses.iter().find(|sc| sc.id == id));
+        let protocol = subclass
+            .zip(protocol_id)
+            .and_then(|(sc, id)| sc.protocols.iter().find(|p| p.id == id));
+        Some(DeviceClass {
+            class: &class.name,
+            subclass: subclass.map(|sc| sc.name.as_str()),
+            protocol: protocol.map(|p| p.name.as_str()),
+        })
     }
 }
 
@@ -76,33 +315,240 @@ impl Default for UsbCache {
     }
 }
 
-/// This function searches the input string for a vendor id (vid) and product id (pid).
-/// Input strings in the form of `USB\VID_1234&PID_5678\9479493` are assumed.
-/// It returns a tuple, where the first value is the vendor id, and the second is the product id. This tuple contains substrings of the initial input string,
-/// so handle lifetimes accordingly.
-fn parse_device_identifier(device_string: &str) -> Result<(u16, u16), NomError<'_>> {
+/// This function searches the input string for a vendor id (vid), product id (pid), and optionally a revision, interface number and class code.
+/// The format of the input is detected with [detect_format], and Windows identifiers, modalias strings and `lsusb` lines are accepted.
+pub fn parse_device_identifier(
+    device_string: &str,
+) -> Result<ParsedIdentifier<'_>, IdentifierError> {
+    let device_string = device_string.trim();
+    let parsed = match detect_format(device_string) {
+        IdentifierFormat::Windows => parse_windows_identifier(device_string),
+        IdentifierFormat::Modalias => parse_modalias(device_string),
+        IdentifierFormat::Lsusb => parse_lsusb_line(device_string),
+        IdentifierFormat::Lspci => Err(nom::Err::Error(nom::error::Error::new(
+            device_string,
+            ErrorKind::Tag,
+        ))),
+    };
+    Ok(parsed?)
+}
+
+/// Read one or more Windows identifiers.
+/// Most Windows hardware IDs that carry a vid and pid are accepted, regardless of case, for example:
+/// - `USB\VID_1234&PID_5678&REV_0100&MI_01\9479493`
+/// - `HID\VID_046D&PID_C092&MI_00&Col01\8&2F1D9E7B&0&0000`
+/// - `SWD\WPDBUSENUM\_??_USB#VID_04E8&PID_6860&MS_COMP_MTP&SAMSUNG_Android#6&3A6E1F2&0&0000#{6ac27878-a6fa-4155-ba85-f98f491d4f33}`
+/// - `BTHENUM\{0000110b-0000-1000-8000-00805f9b34fb}_VID&0002046d_PID&b33e\7&1D4E6A8F&0&BLUETOOTHDEVICE_000000000000`
+///
+/// Compatible IDs in the form of `USB\Class_03&SubClass_01&Prot_02` are also accepted, and only carry a class code.
+/// IDs from storage and printer enumerators (like `USBSTOR\DiskSanDisk_Cruzer___1.00`) don't carry a vid or class, so
+/// only the enumerator is read from them.
+///
+/// The input may also be a list of IDs separated by whitespace, commas or semicolons, in which case the fields from every
+/// ID that could be read are combined. An error is only returned if none of the IDs could be read.
+fn parse_windows_identifier(device_string: &str) -> Result<ParsedIdentifier<'_>, NomError<'_>> {
     // https://learn.microsoft.com/en-us/windows-hardware/drivers/install/standard-usb-identifiers
-    // TODO: this does not fully support all formats of usb device identifiers
-    let vid_combinator = delimited(tag("USB\\VID_"), take(4_u8), take(1_u8))(device_string)?;
-    let pid_combinator = preceded(tag("PID_"), take(4_u8))(vid_combinator.0)?;
-    Ok((
-        u16::from_str_radix(vid_combinator.1, 16).unwrap(),
-        u16::from_str_radix(pid_combinator.1, 16).unwrap(),
-    ))
+    let mut output: Option<ParsedIdentifier> = None;
+    let mut first_error: Option<NomError> = None;
+    for identifier in device_string
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|i| !i.is_empty())
+    {
+        match parse_single_identifier(identifier) {
+            Ok(parsed) => match output {
+                Some(ref mut o) => o.merge(parsed),
+                None => output = Some(parsed),
+            },
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match (output, first_error) {
+        (Some(o), _) => Ok(o),
+        (None, Some(e)) => Err(e),
+        (None, None) => Err(nom::Err::Error(nom::error::Error::new(
+            device_string,
+            ErrorKind::Eof,
+        ))),
+    }
 }
 
-fn parse_usb_db() -> Vec<Vendor> {
-    // this is kind of awful, but there's an invalid utf 8 character at byte 703748,
-    // so we just stop before then, because it's past the section we care about
-    let file_as_str = std::str::from_utf8(&INPUT_FILE[0..703_748]).unwrap();
+/// Enumerators whose IDs don't have to carry a vid or class, only the enumerator is read from them
+const ID_ONLY_ENUMERATORS: [&str; 3] = ["USBSTOR", "USBPRINT", "WPDBUSENUMROOT"];
+
+/// Read the fields out of a single hardware or compatible ID, see [parse_windows_identifier]
+fn parse_single_identifier(identifier: &str) -> Result<ParsedIdentifier<'_>, NomError<'_>> {
+    let (enumerator, rest) =
+        identifier
+            .split_once('\\')
+            .ok_or(nom::Err::Error(nom::error::Error::new(
+                identifier,
+                ErrorKind::Tag,
+            )))?;
+    let mut output = ParsedIdentifier {
+        enumerator: Some(enumerator),
+        ..Default::default()
+    };
+    // the fields are in the hardware id, which is usually directly after the enumerator, but some enumerators (like WPD) nest it
+    // deeper. Anything after the hardware id is an instance id, and isn't read
+    let hardware_id = rest.split('\\').find(|segment| {
+        split_fields(segment)
+            .any(|f| f.eq_ignore_ascii_case("VID") || f.eq_ignore_ascii_case("CLASS"))
+    });
+    let hardware_id = match hardware_id {
+        Some(h) => h,
+        // storage and printer ids name the device with a vendor and product string instead
+        None if ID_ONLY_ENUMERATORS
+            .iter()
+            .any(|e| e.eq_ignore_ascii_case(enumerator)) =>
+        {
+            return Ok(output);
+        }
+        None => {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                rest,
+                ErrorKind::Tag,
+            )))
+        }
+    };
+    let mut vid: Option<u16> = None;
+    let mut pid: Option<u16> = None;
+    let mut class: Option<u8> = None;
+    let mut subclass: Option<u8> = None;
+    let mut protocol: Option<u8> = None;
+    // the hardware id is treated as a list of keys, each directly followed by a value, so `VID_046D&PID_C092` is read as
+    // [`VID`, `046D`, `PID`, `C092`]
+    let mut fields = split_fields(hardware_id);
+    while let Some(key) = fields.next() {
+        let Some(key) = ["VID", "PID", "REV", "MI", "CLASS", "SUBCLASS", "PROT"]
+            .into_iter()
+            .find(|k| k.eq_ignore_ascii_case(key))
+        else {
+            continue;
+        };
+        // an empty input means the identifier was cut short
+        let value = fields.next().ok_or(nom::Err::Error(nom::error::Error::new(
+            &hardware_id[hardware_id.len()..],
+            ErrorKind::Eof,
+        )))?;
+        match key {
+            // bluetooth vids are prefixed with the source of the vid, only `0002` (the USB-IF) can be looked up
+            "VID" if value.len() == 8 => {
+                if parse_hex_field(&value[0..4], 4)? == 0x0002 {
+                    vid = Some(parse_hex_field(&value[4..8], 4)?);
+                }
+            }
+            "VID" => vid = Some(parse_hex_field(value, 4)?),
+            "PID" => pid = Some(parse_hex_field(value, 4)?),
+            // storage devices report their firmware revision as a string, like `REV_1.00`, which can't be looked up
+            "REV" => output.revision = parse_hex_field(value, 4).ok(),
+            "MI" => output.interface = Some(parse_hex_field(value, 2)? as u8),
+            "CLASS" => class = Some(parse_hex_field(value, 2)? as u8),
+            "SUBCLASS" => subclass = Some(parse_hex_field(value, 2)? as u8),
+            _ => protocol = Some(parse_hex_field(value, 2)? as u8),
+        }
+    }
+    output.ids = vid.map(|vid| (vid, pid));
+    // the subclass and protocol are only meaningful if the class is known
+    output.class = class.map(|c| (c, subclass, protocol));
+    Ok(output)
+}
+
+/// Split a hardware id into individual fields
+fn split_fields(hardware_id: &str) -> impl Iterator<Item = &str> {
+    hardware_id.split(['&', '#', '_'])
+}
+
+/// Read a field of exactly `digits` hex digits from an identifier
+fn parse_hex_field(value: &str, digits: usize) -> Result<u16, NomError<'_>> {
+    Ok(all_consuming(hex_digits(digits))(value)?.1)
+}
+
+/// Read a USB modalias string, in the format of:
+///
+/// `usb:v1532p0084d0200dc00dsc00dp00ic03isc01ip02in00`
+///
+/// The interface fields (`ic`, `isc`, `ip` and `in`) are only present for interfaces
+fn parse_modalias(input: &str) -> Result<ParsedIdentifier<'_>, NomError<'_>> {
+    let class_code = |class, subclass, protocol| {
+        tuple((
+            preceded(tag(class), hex_digits(2)),
+            preceded(tag(subclass), hex_digits(2)),
+            preceded(tag(protocol), hex_digits(2)),
+        ))
+    };
+    let (rest, (vid, pid, revision, device_class)) = tuple((
+        preceded(tag("usb:v"), hex_digits(4)),
+        preceded(char('p'), hex_digits(4)),
+        preceded(char('d'), hex_digits(4)),
+        class_code("dc", "dsc", "dp"),
+    ))(input)?;
+    let (_, (interface_class, interface)) = tuple((
+        opt(class_code("ic", "isc", "ip")),
+        opt(preceded(tag("in"), hex_digits(2))),
+    ))(rest)?;
+    // a device class of 00 means that the class is defined by each interface instead
+    let (class, subclass, protocol) = match (device_class, interface_class) {
+        ((0x00, _, _), Some(interface_class)) => interface_class,
+        _ => device_class,
+    };
+    Ok(ParsedIdentifier {
+        ids: Some((vid, Some(pid))),
+        revision: Some(revision),
+        interface: interface.map(|i| i as u8),
+        class: Some((class as u8, Some(subclass as u8), Some(protocol as u8))),
+        ..Default::default()
+    })
+}
+
+/// Read a line of `lsusb` output, in the format of:
+///
+/// `Bus 001 Device 002: ID 1532:0084 Razer USA, Ltd DeathAdder V2`
+fn parse_lsusb_line(input: &str) -> Result<ParsedIdentifier<'_>, NomError<'_>> {
+    let (_, ids) = preceded(
+        tuple((take_until(" ID "), tag(" ID "))),
+        separated_pair(hex_digits(4), char(':'), hex_digits(4)),
+    )(input)?;
+    Ok(ParsedIdentifier {
+        ids: Some((ids.0, Some(ids.1))),
+        ..Default::default()
+    })
+}
+
+/// Decode the whole input file into a string. usb.ids is mostly utf 8, but a handful of characters
+/// (like the `´` in the HID usage tables) are stored as latin-1, so any byte that isn't valid utf 8 is decoded as latin-1 instead
+fn decode_input(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len());
+    for chunk in input.utf8_chunks() {
+        output.push_str(chunk.valid());
+        // latin-1 maps directly onto the first 256 unicode code points
+        output.extend(chunk.invalid().iter().map(|&b| char::from(b)));
+    }
+    output
+}
+
+/// Read the vendor and class lists from the decoded database
+fn parse_usb_db(file_as_str: &str) -> (Vec<Vendor>, Vec<Class>) {
     let header_combinator_output = read_header(file_as_str).unwrap();
     let mut output: Vec<Vendor> = Vec::with_capacity(1024);
-    let mut iterated_output = read_vendor(header_combinator_output.0);
+    let mut leftover = header_combinator_output.0;
+    let mut iterated_output = read_vendor(leftover);
     while let Ok(ref section_output) = iterated_output {
         output.push(section_output.1.clone());
+        leftover = section_output.0;
         iterated_output = read_vendor(section_output.0);
     }
-    output
+
+    // the class list comes directly after the vendor list
+    let class_header_combinator_output = read_class_header(leftover).unwrap();
+    let mut classes: Vec<Class> = Vec::with_capacity(32);
+    let mut iterated_output = read_class(class_header_combinator_output.0);
+    while let Ok(section_output) = iterated_output {
+        classes.push(section_output.1);
+        iterated_output = read_class(section_output.0);
+    }
+    (output, classes)
 }
 
 /// read the commented header up until the
@@ -113,6 +559,64 @@ fn read_header(input: &str) -> IResult<&str, &str> {
     take_until("0001")(input)
 }
 
+/// Read the HID usage tables (`HUT`) from the decoded database
+fn parse_usage_pages(file_as_str: &str) -> Vec<UsagePage> {
+    let section_combinator_output = read_until_section(file_as_str, "HUT ").unwrap();
+    let mut output: Vec<UsagePage> = Vec::with_capacity(32);
+    let mut iterated_output = read_usage_page(section_combinator_output.0);
+    while let Ok(section_output) = iterated_output {
+        output.push(section_output.1);
+        iterated_output = read_usage_page(section_output.0);
+    }
+    output
+}
+
+/// Read the list of languages (`L`) from the decoded database
+fn parse_languages(file_as_str: &str) -> Vec<Language> {
+    let section_combinator_output = read_until_section(file_as_str, "L ").unwrap();
+    let mut output: Vec<Language> = Vec::with_capacity(128);
+    let mut iterated_output = read_language(section_combinator_output.0);
+    while let Ok(section_output) = iterated_output {
+        output.push(section_output.1);
+        iterated_output = read_language(section_output.0);
+    }
+    output
+}
+
+/// Read every line of a flat section where each line is in the form of `key id  name`, like `R 04  Usage Page`,
+/// returning a list of (`id`, `name`) pairs
+fn parse_keyed_section<'a>(file_as_str: &'a str, key: &str) -> Vec<(&'a str, &'a str)> {
+    let section_combinator_output = read_until_section(file_as_str, key).unwrap();
+    let mut output: Vec<(&str, &str)> = Vec::with_capacity(32);
+    let mut iterated_output = read_keyed_line(section_combinator_output.0, key);
+    while let Ok(line_output) = iterated_output {
+        output.push(line_output.1);
+        iterated_output = read_keyed_line(line_output.0, key);
+    }
+    output
+}
+
+/// Skip ahead to the first line that starts with `key`. The sections at the bottom of the file are each
+/// made up of lines that start with the same key, like `HUT ` or `R `
+fn read_until_section<'a>(input: &'a str, key: &str) -> IResult<&'a str, &'a str> {
+    let section_start = format!("\n{key}");
+    // this is bound to a variable so the combinator is dropped before `section_start`
+    let section_combinator = terminated(take_until(section_start.as_str()), tag("\n"))(input);
+    section_combinator
+}
+
+/// Read a single line in the form of `key id  name`, returning a tuple of (`id`, `name`)
+fn read_keyed_line<'a>(input: &'a str, key: &str) -> IResult<&'a str, (&'a str, &'a str)> {
+    let id_combinator = delimited(tag(key), hex_digit1, tag("  "))(input)?;
+    let name_combinator = terminated(take_until("\n"), char('\n'))(id_combinator.0)?;
+    Ok((name_combinator.0, (id_combinator.1, name_combinator.1)))
+}
+
+/// read the commented header of the class list up until the start of the actual list
+fn read_class_header(input: &str) -> IResult<&str, &str> {
+    take_until("C 00  ")(input)
+}
+
 /// This combinator reads a a vendor and all of the associated ids from the file
 fn read_vendor(input: &str) -> IResult<&str, Vendor> {
     // read the vendor id and vendor name
@@ -122,19 +626,20 @@ fn read_vendor(input: &str) -> IResult<&str, Vendor> {
         delimited(tag("  "), take_until("\n"), char('\n'))(vid_combinator_output.0)?;
     let vname = vname_combinator.1;
     // read until the next line doesn't start with a tab
-    let mut devices: Vec<Device> = Vec::new();
+    let mut devices: HashMap<u16, Device, BuildNoHashHasher<u16>> =
+        HashMap::with_hasher(BuildNoHashHasher::default());
     let mut iterated_output = read_device_line(vname_combinator.0);
     // this is so that we can actually return the leftover of the iterated parsing
     let mut leftover = vname_combinator.0;
     loop {
         if let Ok(combinator_output) = iterated_output {
             leftover = combinator_output.0;
-            devices.push(combinator_output.1);
+            devices.insert(combinator_output.1.id, combinator_output.1);
             iterated_output = read_device_line(combinator_output.0);
         } else {
             // Some lines have comments, handle those here, this is assuming the next line is indented
-            if leftover.starts_with('\t') {
-                leftover = take_until("\t")(leftover)?.0;
+            if leftover.starts_with('#') {
+                leftover = preceded(take_until("\n"), char('\n'))(leftover)?.0;
                 iterated_output = read_device_line(leftover);
                 continue;
             }
@@ -167,18 +672,346 @@ fn read_device_line(input: &str) -> IResult<&str, Device> {
     ))
 }
 
+/// This combinator reads a class and all of the associated subclasses and protocols from the file
+fn read_class(input: &str) -> IResult<&str, Class> {
+    let cid_combinator = delimited(tag("C "), take(2_u8), tag("  "))(input)?;
+    let cname_combinator = terminated(take_until("\n"), char('\n'))(cid_combinator.0)?;
+    // read until the next line doesn't start with a tab
+    let mut subclasses: Vec<Subclass> = Vec::new();
+    let mut leftover = cname_combinator.0;
+    while let Ok(combinator_output) = read_subclass(leftover) {
+        leftover = combinator_output.0;
+        subclasses.push(combinator_output.1);
+    }
+
+    Ok((
+        leftover,
+        Class {
+            id: u8::from_str_radix(cid_combinator.1, 16).unwrap(),
+            name: String::from(cname_combinator.1),
+            subclasses,
+        },
+    ))
+}
+
+/// This combinator reads a subclass and all of the associated protocols from the file
+fn read_subclass(input: &str) -> IResult<&str, Subclass> {
+    let scid_combinator = preceded(char('\t'), take(2_u8))(input)?;
+    let scname_combinator = delimited(tag("  "), take_until("\n"), char('\n'))(scid_combinator.0)?;
+    // read until the next line doesn't start with two tabs
+    let mut protocols: Vec<Protocol> = Vec::new();
+    let mut leftover = scname_combinator.0;
+    while let Ok(combinator_output) = read_protocol_line(leftover) {
+        leftover = combinator_output.0;
+        protocols.push(combinator_output.1);
+    }
+
+    Ok((
+        leftover,
+        Subclass {
+            id: u8::from_str_radix(scid_combinator.1, 16).unwrap(),
+            name: String::from(scname_combinator.1),
+            protocols,
+        },
+    ))
+}
+
+/// This combinator reads a single protocol line from the input, if it is formed correctly
+fn read_protocol_line(input: &str) -> IResult<&str, Protocol> {
+    let pid_combinator = delimited(tag("\t\t"), take(2_u8), tag("  "))(input)?;
+    let pname_combinator = terminated(take_until("\n"), char('\n'))(pid_combinator.0)?;
+    Ok((
+        pname_combinator.0,
+        Protocol {
+            id: u8::from_str_radix(pid_combinator.1, 16).unwrap(),
+            name: String::from(pname_combinator.1),
+        },
+    ))
+}
+
+/// This combinator reads a HID usage page and all of the associated usages from the file
+fn read_usage_page(input: &str) -> IResult<&str, UsagePage> {
+    let (mut leftover, (id, name)) = read_keyed_line(input, "HUT ")?;
+    // read until the next line doesn't start with a tab
+    let mut usages: Vec<Usage> = Vec::new();
+    while let Ok(combinator_output) = read_usage_line(leftover) {
+        leftover = combinator_output.0;
+        usages.push(combinator_output.1);
+    }
+
+    Ok((
+        leftover,
+        UsagePage {
+            id: u16::from_str_radix(id, 16).unwrap(),
+            name: String::from(name),
+            usages,
+        },
+    ))
+}
+
+/// This combinator reads a single HID usage line from the input, if it is formed correctly
+fn read_usage_line(input: &str) -> IResult<&str, Usage> {
+    let id_combinator = delimited(char('\t'), hex_digit1, tag("  "))(input)?;
+    let name_combinator = terminated(take_until("\n"), char('\n'))(id_combinator.0)?;
+    Ok((
+        name_combinator.0,
+        Usage {
+            id: u16::from_str_radix(id_combinator.1, 16).unwrap(),
+            name: String::from(name_combinator.1),
+        },
+    ))
+}
+
+/// This combinator reads a language and all of the associated dialects from the file
+fn read_language(input: &str) -> IResult<&str, Language> {
+    let (mut leftover, (id, name)) = read_keyed_line(input, "L ")?;
+    // read until the next line doesn't start with a tab
+    let mut dialects: Vec<Dialect> = Vec::new();
+    while let Ok(combinator_output) = read_dialect_line(leftover) {
+        leftover = combinator_output.0;
+        dialects.push(combinator_output.1);
+    }
+
+    Ok((
+        leftover,
+        Language {
+            id: u16::from_str_radix(id, 16).unwrap(),
+            name: String::from(name),
+            dialects,
+        },
+    ))
+}
+
+/// This combinator reads a single dialect line from the input, if it is formed correctly
+fn read_dialect_line(input: &str) -> IResult<&str, Dialect> {
+    let id_combinator = delimited(char('\t'), hex_digit1, tag("  "))(input)?;
+    let name_combinator = terminated(take_until("\n"), char('\n'))(id_combinator.0)?;
+    Ok((
+        name_combinator.0,
+        Dialect {
+            id: u8::from_str_radix(id_combinator.1, 16).unwrap(),
+            name: String::from(name_combinator.1),
+        },
+    ))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_device_identifier;
+    use nohash_hasher::BuildNoHashHasher;
+    use std::collections::HashMap;
+
+    use super::ParsedIdentifier;
+    use super::{decode_input, parse_device_identifier, DeviceClass, UsbCache, INPUT_FILE};
     use super::{parse_usb_db, read_vendor};
+    use super::{read_class, read_protocol_line, read_subclass, Class, Protocol, Subclass};
     use super::{read_device_line, read_header, Device, Vendor};
+    use super::{read_keyed_line, read_until_section, read_usage_page, Usage, UsagePage};
+    use super::{read_language, Dialect, Language};
+    use crate::identifier::IdentifierError;
 
     #[test]
     fn basic_parse_device_string() {
         let mock_device_string = "USB\\VID_1234&PID_5678\\9479493";
         assert_eq!(
             parse_device_identifier(mock_device_string),
-            Ok((0x1234, 0x5678))
+            Ok(ParsedIdentifier {
+                enumerator: Some("USB"),
+                ids: Some((0x1234, Some(0x5678))),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_device_identifier("USB\\VID_046D&PID_C092&MI_01\\7&384A91C&1&0001"),
+            Ok(ParsedIdentifier {
+                enumerator: Some("USB"),
+                ids: Some((0x046d, Some(0xc092))),
+                interface: Some(0x01),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_device_identifier("USB\\VID_046D&PID_C092&REV_0100&MI_00"),
+            Ok(ParsedIdentifier {
+                enumerator: Some("USB"),
+                ids: Some((0x046d, Some(0xc092))),
+                revision: Some(0x0100),
+                interface: Some(0x00),
+                ..Default::default()
+            })
+        );
+        // the instance id should not be searched for an interface number
+        assert_eq!(
+            parse_device_identifier("USB\\VID_046D&PID_C092\\7&MI_01"),
+            Ok(ParsedIdentifier {
+                enumerator: Some("USB"),
+                ids: Some((0x046d, Some(0xc092))),
+                ..Default::default()
+            })
+        );
+        // prefixes aren't case sensitive
+        assert_eq!(
+            parse_device_identifier("usb\\vid_046d&pid_c092&rev_0100"),
+            Ok(ParsedIdentifier {
+                enumerator: Some("usb"),
+                ids: Some((0x046d, Some(0xc092))),
+                revision: Some(0x0100),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_other_enumerators() {
+        assert_eq!(
+            parse_device_identifier("HID\\VID_046D&PID_C092&MI_00&Col01\\8&2F1D9E7B&0&0000"),
+            Ok(ParsedIdentifier {
+                enumerator: Some("HID"),
+                ids: Some((0x046d, Some(0xc092))),
+                interface: Some(0x00),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_device_identifier(
+                "SWD\\WPDBUSENUM\\_??_USB#VID_04E8&PID_6860&MS_COMP_MTP&SAMSUNG_Android#6&3A6E1F2&0&0000#{6ac27878-a6fa-4155-ba85-f98f491d4f33}"
+            ),
+            Ok(ParsedIdentifier {
+                enumerator: Some("SWD"),
+                ids: Some((0x04e8, Some(0x6860))),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_device_identifier(
+                "BTHENUM\\{0000110b-0000-1000-8000-00805f9b34fb}_VID&0002046d_PID&b33e\\7&1D4E6A8F&0&BLUETOOTHDEVICE_000000000000"
+            ),
+            Ok(ParsedIdentifier {
+                enumerator: Some("BTHENUM"),
+                ids: Some((0x046d, Some(0xb33e))),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_device_identifier("USBSTOR\\DiskSanDisk_Cruzer___1.00\\4C530001230817114023&0"),
+            Ok(ParsedIdentifier {
+                enumerator: Some("USBSTOR"),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_device_identifier("USBPRINT\\HPENVY_4500_series\\7&2C6F1E4D&0&USB001"),
+            Ok(ParsedIdentifier {
+                enumerator: Some("USBPRINT"),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_device_identifier(
+                "WPDBUSENUMROOT\\UMB\\2&37C186B&1&STORAGE#VOLUME#_??_USBSTOR#DISK&VEN_SANDISK&PROD_CRUZER&REV_1.00#4C530001230817114023&0#"
+            ),
+            Ok(ParsedIdentifier {
+                enumerator: Some("WPDBUSENUMROOT"),
+                ..Default::default()
+            })
+        );
+        // a vendor id is kept even without a product id
+        assert_eq!(
+            parse_device_identifier("USB\\VID_046D").map(|p| p.ids),
+            Ok(Some((0x046d, None)))
+        );
+        // revisions that aren't hex are skipped instead of failing the whole id
+        assert_eq!(
+            parse_device_identifier("USB\\VID_0781&PID_5567&Rev_1.00"),
+            Ok(ParsedIdentifier {
+                enumerator: Some("USB"),
+                ids: Some((0x0781, Some(0x5567))),
+                ..Default::default()
+            })
+        );
+        // vids assigned by the bluetooth sig aren't usb vids
+        assert_eq!(
+            parse_device_identifier(
+                "BTHENUM\\{0000110b-0000-1000-8000-00805f9b34fb}_VID&0001004c_PID&200e"
+            )
+            .map(|p| p.ids),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn parse_identifier_list() {
+        assert_eq!(
+            parse_device_identifier(
+                "USB\\VID_046D&PID_C092&REV_0100&MI_00, USB\\VID_046D&PID_C092&MI_00; USB\\Class_03&SubClass_01&Prot_02"
+            ),
+            Ok(ParsedIdentifier {
+                enumerator: Some("USB"),
+                ids: Some((0x046d, Some(0xc092))),
+                revision: Some(0x0100),
+                interface: Some(0x00),
+                class: Some((0x03, Some(0x01), Some(0x02))),
+            })
+        );
+        // one bad id shouldn't spoil the rest of the list
+        assert!(parse_device_identifier("garbage USB\\VID_046D&PID_C092").is_ok());
+    }
+
+    #[test]
+    fn parse_invalid_device_string() {
+        // non-hex digits should return an error, not panic
+        assert!(parse_device_identifier("USB\\VID_04ZZ&PID_C092").is_err());
+        assert!(parse_device_identifier("USB\\VID_046D&PID_C09").is_err());
+        assert!(parse_device_identifier("USB\\VID_046D&PID_").is_err());
+        assert!(parse_device_identifier("USB\\VID_046D&PID").is_err());
+        assert!(parse_device_identifier("USB\\ROOT_HUB30").is_err());
+        assert!(parse_device_identifier("").is_err());
+    }
+
+    #[test]
+    fn parse_linux_identifiers() {
+        assert_eq!(
+            parse_device_identifier("usb:v1532p0084d0200dc00dsc00dp00ic03isc01ip02in03"),
+            Ok(ParsedIdentifier {
+                ids: Some((0x1532, Some(0x0084))),
+                revision: Some(0x0200),
+                interface: Some(0x03),
+                class: Some((0x03, Some(0x01), Some(0x02))),
+                ..Default::default()
+            })
+        );
+        // the device class should be used if it's not defined per interface
+        assert_eq!(
+            parse_device_identifier("usb:v046DpC52Bd2411dc09dsc00dp01").map(|p| p.class),
+            Ok(Some((0x09, Some(0x00), Some(0x01))))
+        );
+        assert_eq!(
+            parse_device_identifier(
+                "Bus 001 Device 002: ID 1532:0084 Razer USA, Ltd DeathAdder V2"
+            ),
+            Ok(ParsedIdentifier {
+                ids: Some((0x1532, Some(0x0084))),
+                ..Default::default()
+            })
+        );
+        assert!(parse_device_identifier("usb:v15Z2p0084").is_err());
+        assert!(parse_device_identifier("00:14.0 0c03: 8086:9d2f (rev 21)").is_err());
+    }
+
+    #[test]
+    fn parse_compatible_id() {
+        let class = |c| parse_device_identifier(c).map(|p| p.class);
+        assert_eq!(
+            class("USB\\Class_03&SubClass_01&Prot_02"),
+            Ok(Some((0x03, Some(0x01), Some(0x02))))
+        );
+        assert_eq!(
+            class("USB\\Class_03&SubClass_01"),
+            Ok(Some((0x03, Some(0x01), None)))
+        );
+        assert_eq!(class("USB\\Class_e0"), Ok(Some((0xe0, None, None))));
+        assert_eq!(
+            class("USB\\CLASS_03&SUBCLASS_01&PROT_02"),
+            Ok(Some((0x03, Some(0x01), Some(0x02))))
         );
     }
 
@@ -197,10 +1030,37 @@ mod tests {
         let expected_output = Vendor {
             id: 0x1234,
             name: String::from("vendor_name"),
-            devices: vec![Device {
+            devices: [Device {
                 id: 0x5678,
                 name: String::from("device_name"),
-            }],
+            }]
+            .into_iter()
+            .map(|d| (d.id, d))
+            .collect(),
+        };
+        assert_eq!(read_vendor(mock_section), Ok(("9123", expected_output)));
+    }
+
+    #[test]
+    fn read_vendor_with_comment() {
+        let mock_section =
+            "1234  vendor_name\n\t5678  device_name\n# typo?\n\t9abc  other_device\n9123";
+        let expected_output = Vendor {
+            id: 0x1234,
+            name: String::from("vendor_name"),
+            devices: [
+                Device {
+                    id: 0x5678,
+                    name: String::from("device_name"),
+                },
+                Device {
+                    id: 0x9abc,
+                    name: String::from("other_device"),
+                },
+            ]
+            .into_iter()
+            .map(|d| (d.id, d))
+            .collect(),
         };
         assert_eq!(read_vendor(mock_section), Ok(("9123", expected_output)));
     }
@@ -211,7 +1071,7 @@ mod tests {
         let expected_output = Vendor {
             id: 0x1234,
             name: String::from("vendor_name"),
-            devices: vec![],
+            devices: HashMap::with_hasher(BuildNoHashHasher::default()),
         };
         assert_eq!(read_vendor(mock_section), Ok(("5678", expected_output)));
         // first make sure we can read a normal device without issue
@@ -228,8 +1088,234 @@ mod tests {
         );
     }
 
+    #[test]
+    fn basic_read_class() {
+        let mock_section =
+            "C 03  Human Interface Device\n\t01  Boot Interface Subclass\n\t\t01  Keyboard\nC 05";
+        let expected_output = Class {
+            id: 0x03,
+            name: String::from("Human Interface Device"),
+            subclasses: vec![Subclass {
+                id: 0x01,
+                name: String::from("Boot Interface Subclass"),
+                protocols: vec![Protocol {
+                    id: 0x01,
+                    name: String::from("Keyboard"),
+                }],
+            }],
+        };
+        assert_eq!(read_class(mock_section), Ok(("C 05", expected_output)));
+        assert_eq!(
+            read_subclass("\t42  Controller\n\t43"),
+            Ok((
+                "\t43",
+                Subclass {
+                    id: 0x42,
+                    name: String::from("Controller"),
+                    protocols: vec![],
+                }
+            ))
+        );
+        assert_eq!(
+            read_protocol_line("\t\t50  Bulk-Only\nC 09"),
+            Ok((
+                "C 09",
+                Protocol {
+                    id: 0x50,
+                    name: String::from("Bulk-Only"),
+                }
+            ))
+        );
+    }
+
     #[test]
     fn basic_parse_usbs() {
-        parse_usb_db();
+        let (vendors, classes) = parse_usb_db(&decode_input(INPUT_FILE));
+        // make sure the vendor list was read all the way to the end, and the class list after it
+        assert_eq!(vendors.last().map(|v| v.id), Some(0xffee));
+        assert_eq!(classes.first().map(|c| c.id), Some(0x00));
+        assert_eq!(classes.last().map(|c| c.id), Some(0xff));
+    }
+
+    #[test]
+    fn basic_read_keyed_line() {
+        assert_eq!(
+            read_keyed_line("R 04  Usage Page\nR 08", "R "),
+            Ok(("R 08", ("04", "Usage Page")))
+        );
+        assert_eq!(
+            read_keyed_line("BIAS 1  Right Hand\n", "BIAS "),
+            Ok(("", ("1", "Right Hand")))
+        );
+        assert!(read_keyed_line("\n# List of Languages", "R ").is_err());
+        assert_eq!(
+            read_until_section("R c0  End\n\n# foo\n\nBIAS 0  N/A\n", "BIAS "),
+            Ok(("BIAS 0  N/A\n", "R c0  End\n\n# foo\n"))
+        );
+    }
+
+    #[test]
+    fn basic_read_usage_page() {
+        let mock_section =
+            "HUT 01  Generic Desktop Controls\n\t002  Mouse\n\t006  Keyboard\nHUT 02";
+        let expected_output = UsagePage {
+            id: 0x01,
+            name: String::from("Generic Desktop Controls"),
+            usages: vec![
+                Usage {
+                    id: 0x002,
+                    name: String::from("Mouse"),
+                },
+                Usage {
+                    id: 0x006,
+                    name: String::from("Keyboard"),
+                },
+            ],
+        };
+        assert_eq!(
+            read_usage_page(mock_section),
+            Ok(("HUT 02", expected_output))
+        );
+    }
+
+    #[test]
+    fn find_hid_tables() {
+        let cache = UsbCache::new();
+        let (page, usage) = cache.find_hid_usage(0x01, Some(0x06)).unwrap();
+        assert_eq!(page.name, "Generic Desktop Controls");
+        assert_eq!(usage.map(|u| u.name.as_str()), Some("Keyboard"));
+        // the last usage page in the file
+        let (page, usage) = cache.find_hid_usage(0xff, None).unwrap();
+        assert_eq!(page.name, "Vendor Specific");
+        assert_eq!(usage, None);
+        assert!(cache.find_hid_usage(0x06, Some(0x01)).is_none());
+
+        // 0x05 is a usage page item with one byte of data
+        assert_eq!(cache.find_report_item(0x05), Some("Usage Page"));
+        assert_eq!(cache.find_report_item(0xc0), Some("End Collection"));
+        assert_eq!(cache.find_bias(0x02), Some("Left Hand"));
+    }
+
+    #[test]
+    fn basic_read_language() {
+        let mock_section = "L 0060  Kashmiri\n\t02  India\nL 0061";
+        let expected_output = Language {
+            id: 0x0060,
+            name: String::from("Kashmiri"),
+            dialects: vec![Dialect {
+                id: 0x02,
+                name: String::from("India"),
+            }],
+        };
+        assert_eq!(read_language(mock_section), Ok(("L 0061", expected_output)));
+    }
+
+    #[test]
+    fn find_language_and_audio_terminal() {
+        let cache = UsbCache::new();
+        let (language, dialect) = cache.find_language(0x0409).unwrap();
+        assert_eq!(language.name, "English");
+        assert_eq!(dialect.map(|d| d.name.as_str()), Some("US"));
+        let (language, dialect) = cache.find_language(0x0809).unwrap();
+        assert_eq!(language.name, "English");
+        assert_eq!(dialect.map(|d| d.name.as_str()), Some("UK"));
+        // the last language in the file, with no dialect set
+        let (language, dialect) = cache.find_language(0x0061).unwrap();
+        assert_eq!(language.name, "Nepali");
+        assert_eq!(dialect, None);
+        assert!(cache.find_language(0x03ff).is_none());
+
+        assert_eq!(cache.find_audio_terminal(0x0302), Some("Headphones"));
+        assert_eq!(cache.find_audio_terminal(0x0402), Some("Headset"));
+        assert_eq!(cache.find_audio_terminal(0x0000), None);
+    }
+
+    #[test]
+    fn decode_whole_file() {
+        let decoded = decode_input(INPUT_FILE);
+        // the trailing sections of the file should not be cut off
+        assert!(decoded.contains("\nAT 0302  Headphones\n"));
+        assert!(decoded.ends_with("VT 0403  Component Video\n"));
+        // invalid utf 8 should be decoded as latin-1, rather than dropped or replaced
+        assert!(decoded.contains("\t034  ´ and \" (Accent Acute and Double Quotes)\n"));
+        assert_eq!(decode_input(b"a\xb4b"), "a´b");
+    }
+
+    #[test]
+    fn find_class() {
+        let cache = UsbCache::new();
+        let info = cache.find("USB\\Class_03&SubClass_01&Prot_01").unwrap();
+        assert_eq!(
+            (info.vendor, info.device, info.interface),
+            (None, None, None)
+        );
+        assert_eq!(
+            info.class,
+            Some(DeviceClass {
+                class: "Human Interface Device",
+                subclass: Some("Boot Interface Subclass"),
+                protocol: Some("Keyboard"),
+            })
+        );
+
+        let info = cache
+            .find("USB\\VID_046D&PID_C336&MI_00\\7&20BE3E95&0&0000")
+            .unwrap();
+        assert_eq!(info.vendor, Some("Logitech, Inc."));
+        assert_eq!(info.interface, Some(0));
+        assert_eq!(info.class, None);
+    }
+
+    #[test]
+    fn find_hid_device() {
+        let cache = UsbCache::new();
+        let info = cache
+            .find("hid\\vid_046d&pid_c336&rev_0100&mi_00&col01")
+            .unwrap();
+        assert_eq!(info.vendor, Some("Logitech, Inc."));
+        assert_eq!(info.enumerator, Some("hid"));
+        assert_eq!(info.revision, Some(0x0100));
+
+        let info = cache
+            .find("Bus 001 Device 004: ID 046d:c336 Logitech, Inc. G213 Prodigy Gaming Keyboard")
+            .unwrap();
+        assert_eq!(info.vendor, Some("Logitech, Inc."));
+        assert_eq!(info.enumerator, None);
+    }
+
+    #[test]
+    fn find_errors() {
+        let cache = UsbCache::new();
+        assert_eq!(
+            cache.find("USB\\VID_04ZZ&PID_C092"),
+            Err(IdentifierError::NonHexDigit(String::from("04ZZ")))
+        );
+        assert_eq!(
+            cache.find("USB\\VID_046D&PID"),
+            Err(IdentifierError::TruncatedField(String::new()))
+        );
+        assert_eq!(
+            cache.find("USB\\ROOT_HUB30\\4&1F4A8D3A&0&0"),
+            Err(IdentifierError::MalformedPrefix(String::from(
+                "ROOT_HUB30\\4&1F4A8D3A&0&0"
+            )))
+        );
+        assert_eq!(
+            cache.find("USB\\VID_FFFF&PID_0000"),
+            Err(IdentifierError::UnknownVendor(0xffff))
+        );
+        // an unknown device still has its vendor and class named
+        let info = cache
+            .find("USB\\VID_046D&PID_FFFE USB\\Class_03&SubClass_01&Prot_01")
+            .unwrap();
+        assert_eq!(info.vendor, Some("Logitech, Inc."));
+        assert_eq!(info.device, None);
+        assert_eq!(info.class.unwrap().class, "Human Interface Device");
+        assert_eq!(
+            cache
+                .find("USB\\VID_FFFF&PID_0000&Class_03")
+                .map(|info| info.vendor),
+            Ok(None)
+        );
     }
 }
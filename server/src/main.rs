@@ -17,6 +17,7 @@ use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::Resource;
 use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::env;
+use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 use tracing::{Level, info, info_span};
@@ -72,8 +73,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let app = Router::new()
         .route("/api/hello/", get(|| async { "hi mom!" }))
         .route("/api/cpus/", get(get_cpu_handler))
+        .route("/api/cpus/", post(post_cpus_handler))
+        .route("/api/cpus/by-part/", get(get_cpu_by_part_handler))
+        .route("/api/cpus/search/", get(get_cpu_search_handler))
+        .route("/api/cpus/suggest/", get(get_cpu_suggest_handler))
+        .route("/api/cpus/compare/", post(post_cpu_compare_handler))
         .route("/api/usbs/", get(get_usb_handler))
         .route("/api/usbs/", post(post_usbs_handler))
+        .route("/api/usb/hid/", get(get_hid_usage_handler))
+        .route("/api/usb/language/", get(get_usb_language_handler))
+        .route("/api/usb/audio-terminal/", get(get_audio_terminal_handler))
         .route("/api/pcie/", get(get_pcie_handler))
         .route("/api/pcie/", post(post_pcie_handler))
         .route("/api/bugcheck/", get(get_bugcheck_handler))
@@ -101,10 +110,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }),
         )
         .with_state(AppState {
-            cpu_cache: CpuCache::new(),
-            usb_cache: UsbCache::new(),
-            pcie_cache: PcieCache::new(),
-            bugcheck_cache: BugCheckCache::new(),
+            cpu_cache: Arc::new(CpuCache::new()),
+            usb_cache: Arc::new(UsbCache::new()),
+            pcie_cache: Arc::new(PcieCache::new()),
+            bugcheck_cache: Arc::new(BugCheckCache::new()),
         });
 
     let mut port: String = cli_args.port;